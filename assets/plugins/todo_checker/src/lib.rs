@@ -0,0 +1,71 @@
+// An example rustptty plugin agent. It fails the run when the generated backend still has
+// todo!() or unimplemented!() in it. Build it with `cargo build --release` and list the
+// library under [[plugins]] in agents.toml
+use std::ffi::{c_char, CStr, CString};
+
+use serde_json::{json, Value};
+
+const ABI_VERSION: u32 = 1;
+const PLACEHOLDERS: [&str; 2] = ["todo!(", "unimplemented!("];
+
+#[no_mangle]
+pub extern "C" fn rustptty_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+#[no_mangle]
+pub extern "C" fn rustptty_agent_manifest() -> *mut c_char {
+    into_raw(json!({
+        "name": "todo_checker",
+        "description": "Fails the run when the backend has todo!() or unimplemented!() left",
+        "position": 35,
+        "reads": ["backend_files"],
+        "writes": []
+    }))
+}
+
+/// # Safety
+/// `factsheet_json` must be a NUL terminated string that stays valid during the call
+#[no_mangle]
+pub unsafe extern "C" fn rustptty_agent_execute(factsheet_json: *const c_char) -> *mut c_char {
+    let input = CStr::from_ptr(factsheet_json).to_string_lossy();
+    let factsheet: Value = match serde_json::from_str(&input) {
+        Ok(factsheet) => factsheet,
+        Err(error) => return into_raw(json!({ "error": error.to_string() })),
+    };
+
+    let unfinished = factsheet["backend_files"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, code)| {
+            code.as_str().is_some_and(|code| {
+                PLACEHOLDERS
+                    .iter()
+                    .any(|macro_call| code.contains(macro_call))
+            })
+        })
+        .map(|(path, _)| path.as_str())
+        .collect::<Vec<_>>();
+
+    if unfinished.is_empty() {
+        into_raw(json!({ "factsheet": factsheet }))
+    } else {
+        into_raw(json!({ "error": format!("Unfinished code in {}", unfinished.join(", ")) }))
+    }
+}
+
+/// # Safety
+/// `content` must come from this library and be freed once only
+#[no_mangle]
+pub unsafe extern "C" fn rustptty_free_string(content: *mut c_char) {
+    if !content.is_null() {
+        drop(CString::from_raw(content));
+    }
+}
+
+fn into_raw(value: Value) -> *mut c_char {
+    CString::new(value.to_string())
+        .expect("JSON has no NUL bytes")
+        .into_raw()
+}
@@ -0,0 +1,78 @@
+use actix_cors::Cors;
+
+use actix_web::{http::header, web, App, HttpResponse, HttpServer, Responder};
+
+use serde_json::Value;
+
+use reqwest::Client as HttpClient;
+
+use std::env;
+use std::fs;
+
+fn external_api_url() -> String {
+    env::var("EXTERNAL_API_URL").unwrap_or_else(|_| "https://api.example.com".to_string())
+}
+
+struct AppState {
+    http_client: HttpClient,
+    external_api_url: String,
+}
+
+// Forwards GET /external/{resource} to the external API and relays its JSON
+async fn read_external(
+    app_state: web::Data<AppState>,
+    resource: web::Path<String>,
+) -> impl Responder {
+    let url = format!("{}/{}", app_state.external_api_url, resource.into_inner());
+
+    let response = match app_state.http_client.get(&url).send().await {
+        Ok(response) => response,
+        Err(_) => return HttpResponse::BadGateway().finish(),
+    };
+
+    match response.error_for_status() {
+        Ok(response) => match response.json::<Value>().await {
+            Ok(body) => HttpResponse::Ok().json(body),
+            Err(_) => HttpResponse::BadGateway().finish(),
+        },
+        Err(_) => HttpResponse::BadGateway().finish(),
+    }
+}
+
+async fn index() -> impl Responder {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    let data: web::Data<AppState> = web::Data::new(AppState {
+        http_client: HttpClient::new(),
+        external_api_url: external_api_url(),
+    });
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(
+                Cors::permissive()
+                    .allowed_origin_fn(|origin, _req_head| {
+                        origin.as_bytes().starts_with(b"http://localhost") || origin == "null"
+                    })
+                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+                    .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])
+                    .allowed_header(header::CONTENT_TYPE)
+                    .supports_credentials()
+                    .max_age(3600),
+            )
+            .app_data(data.clone())
+            .route("/", web::get().to(index))
+            .route("/external/{resource}", web::get().to(read_external))
+    })
+    .bind(env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string()))?
+    .run()
+    .await
+}
@@ -0,0 +1,52 @@
+use actix_cors::Cors;
+
+use actix_web::{http::header, web, App, HttpResponse, HttpServer, Responder};
+
+use serde::{Deserialize, Serialize};
+
+use std::env;
+use std::fs;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Health {
+    status: String,
+}
+
+async fn health() -> impl Responder {
+    let health = Health {
+        status: "ok".to_string(),
+    };
+    HttpResponse::Ok().json(health)
+}
+
+async fn index() -> impl Responder {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .wrap(
+                Cors::permissive()
+                    .allowed_origin_fn(|origin, _req_head| {
+                        origin.as_bytes().starts_with(b"http://localhost") || origin == "null"
+                    })
+                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+                    .allowed_headers(vec![header::AUTHORIZATION, header::ACCEPT])
+                    .allowed_header(header::CONTENT_TYPE)
+                    .supports_credentials()
+                    .max_age(3600),
+            )
+            .route("/", web::get().to(index))
+            .route("/health", web::get().to(health))
+    })
+    .bind(env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string()))?
+    .run()
+    .await
+}
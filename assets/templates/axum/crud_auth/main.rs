@@ -0,0 +1,211 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+    Json, Router,
+};
+
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn database_path() -> String {
+    env::var("DATABASE_PATH").unwrap_or_else(|_| "database.json".to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+    completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct User {
+    id: u64,
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Database {
+    tasks: HashMap<u64, Task>,
+    users: HashMap<u64, User>,
+}
+
+impl Database {
+    fn new() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            users: HashMap::new(),
+        }
+    }
+
+    // CRUD DATA
+    fn insert(&mut self, task: Task) {
+        self.tasks.insert(task.id, task);
+    }
+
+    fn get(&self, id: &u64) -> Option<&Task> {
+        self.tasks.get(id)
+    }
+
+    fn get_all(&self) -> Vec<&Task> {
+        self.tasks.values().collect()
+    }
+
+    fn delete(&mut self, id: &u64) {
+        self.tasks.remove(id);
+    }
+
+    fn update(&mut self, task: Task) {
+        self.tasks.insert(task.id, task);
+    }
+
+    // USER DATA RELATED FUNCTIONS
+    fn insert_user(&mut self, user: User) {
+        self.users.insert(user.id, user);
+    }
+
+    fn get_user_by_name(&self, username: &str) -> Option<&User> {
+        self.users.values().find(|u| u.username == username)
+    }
+
+    // DATABASE SAVING
+    fn save_to_file(&self) -> std::io::Result<()> {
+        let data: String = serde_json::to_string(&self)?;
+        let mut file: fs::File = fs::File::create(database_path())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn load_from_file() -> std::io::Result<Self> {
+        let file_content: String = fs::read_to_string(database_path())?;
+        let db: Database = serde_json::from_str(&file_content)?;
+        Ok(db)
+    }
+}
+
+struct AppState {
+    db: Mutex<Database>,
+}
+
+type SharedState = Arc<AppState>;
+
+async fn create_task(
+    State(app_state): State<SharedState>,
+    Json(task): Json<Task>,
+) -> impl IntoResponse {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.insert(task);
+    let _ = db.save_to_file();
+    StatusCode::OK
+}
+
+async fn read_task(State(app_state): State<SharedState>, Path(id): Path<u64>) -> impl IntoResponse {
+    let db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    match db.get(&id) {
+        Some(task) => Json(task.clone()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+async fn read_all_tasks(State(app_state): State<SharedState>) -> impl IntoResponse {
+    let db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    let tasks: Vec<Task> = db.get_all().into_iter().cloned().collect();
+    Json(tasks)
+}
+
+async fn update_task(
+    State(app_state): State<SharedState>,
+    Json(task): Json<Task>,
+) -> impl IntoResponse {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.update(task);
+    let _ = db.save_to_file();
+    StatusCode::OK
+}
+
+async fn delete_task(
+    State(app_state): State<SharedState>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.delete(&id);
+    let _ = db.save_to_file();
+    StatusCode::OK
+}
+
+async fn register(
+    State(app_state): State<SharedState>,
+    Json(user): Json<User>,
+) -> impl IntoResponse {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.insert_user(user);
+    let _ = db.save_to_file();
+    StatusCode::OK
+}
+
+async fn login(State(app_state): State<SharedState>, Json(user): Json<User>) -> impl IntoResponse {
+    let db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    match db.get_user_by_name(&user.username) {
+        Some(stored_user) if stored_user.password == user.password => {
+            (StatusCode::OK, "Logged in!")
+        }
+        _ => (StatusCode::BAD_REQUEST, "Invalid username or password"),
+    }
+}
+
+async fn index() -> impl IntoResponse {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => Html(html).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn app(app_state: SharedState) -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(
+            |origin: &HeaderValue, _request_parts| {
+                origin.as_bytes().starts_with(b"http://localhost") || origin == "null"
+            },
+        ))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::ACCEPT, header::CONTENT_TYPE])
+        .allow_credentials(true)
+        .max_age(Duration::from_secs(3600));
+
+    Router::new()
+        .route("/", get(index))
+        .route(
+            "/task",
+            post(create_task).get(read_all_tasks).put(update_task),
+        )
+        .route("/task/{id}", get(read_task).delete(delete_task))
+        .route("/register", post(register))
+        .route("/login", post(login))
+        .layer(cors)
+        .with_state(app_state)
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let db: Database = match Database::load_from_file() {
+        Ok(db) => db,
+        Err(_) => Database::new(),
+    };
+
+    let app_state: SharedState = Arc::new(AppState { db: Mutex::new(db) });
+
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, app(app_state)).await
+}
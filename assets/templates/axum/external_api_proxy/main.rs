@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use serde_json::Value;
+
+use reqwest::Client as HttpClient;
+
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn external_api_url() -> String {
+    env::var("EXTERNAL_API_URL").unwrap_or_else(|_| "https://api.example.com".to_string())
+}
+
+struct AppState {
+    http_client: HttpClient,
+    external_api_url: String,
+}
+
+type SharedState = Arc<AppState>;
+
+// Forwards GET /external/{resource} to the external API and relays its JSON
+async fn read_external(
+    State(app_state): State<SharedState>,
+    Path(resource): Path<String>,
+) -> Result<Json<Value>, StatusCode> {
+    let url = format!("{}/{}", app_state.external_api_url, resource);
+
+    let response = app_state
+        .http_client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| StatusCode::BAD_GATEWAY)?;
+
+    let body: Value = response.json().await.map_err(|_| StatusCode::BAD_GATEWAY)?;
+    Ok(Json(body))
+}
+
+async fn index() -> impl IntoResponse {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => Html(html).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn app(app_state: SharedState) -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(
+            |origin: &HeaderValue, _request_parts| {
+                origin.as_bytes().starts_with(b"http://localhost") || origin == "null"
+            },
+        ))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::ACCEPT, header::CONTENT_TYPE])
+        .allow_credentials(true)
+        .max_age(Duration::from_secs(3600));
+
+    Router::new()
+        .route("/", get(index))
+        .route("/external/{resource}", get(read_external))
+        .layer(cors)
+        .with_state(app_state)
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let app_state: SharedState = Arc::new(AppState {
+        http_client: HttpClient::new(),
+        external_api_url: external_api_url(),
+    });
+
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, app(app_state)).await
+}
@@ -0,0 +1,57 @@
+use axum::{
+    http::{header, HeaderValue, Method, StatusCode},
+    response::{Html, IntoResponse},
+    routing::get,
+    Json, Router,
+};
+
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use serde::{Deserialize, Serialize};
+
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Health {
+    status: String,
+}
+
+async fn health() -> Json<Health> {
+    Json(Health {
+        status: "ok".to_string(),
+    })
+}
+
+async fn index() -> impl IntoResponse {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => Html(html).into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn app() -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(
+            |origin: &HeaderValue, _request_parts| {
+                origin.as_bytes().starts_with(b"http://localhost") || origin == "null"
+            },
+        ))
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::AUTHORIZATION, header::ACCEPT, header::CONTENT_TYPE])
+        .allow_credentials(true)
+        .max_age(Duration::from_secs(3600));
+
+    Router::new()
+        .route("/", get(index))
+        .route("/health", get(health))
+        .layer(cors)
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    axum::serve(listener, app()).await
+}
@@ -0,0 +1,195 @@
+use rocket::{
+    delete,
+    fairing::{Fairing, Info, Kind},
+    get,
+    http::{ContentType, Header, Status},
+    launch, options, post, put, routes,
+    serde::json::Json,
+    Request, Response, State,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+fn database_path() -> String {
+    env::var("DATABASE_PATH").unwrap_or_else(|_| "database.json".to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Task {
+    id: u64,
+    name: String,
+    completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Database {
+    tasks: HashMap<u64, Task>,
+}
+
+impl Database {
+    fn new() -> Self {
+        Self {
+            tasks: HashMap::new(),
+        }
+    }
+
+    // CRUD DATA
+    fn insert(&mut self, task: Task) {
+        self.tasks.insert(task.id, task);
+    }
+
+    fn get(&self, id: &u64) -> Option<&Task> {
+        self.tasks.get(id)
+    }
+
+    fn get_all(&self) -> Vec<&Task> {
+        self.tasks.values().collect()
+    }
+
+    fn delete(&mut self, id: &u64) {
+        self.tasks.remove(id);
+    }
+
+    fn update(&mut self, task: Task) {
+        self.tasks.insert(task.id, task);
+    }
+
+    // DATABASE SAVING
+    fn save_to_file(&self) -> std::io::Result<()> {
+        let data: String = serde_json::to_string(&self)?;
+        let mut file: fs::File = fs::File::create(database_path())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn load_from_file() -> std::io::Result<Self> {
+        let file_content: String = fs::read_to_string(database_path())?;
+        let db: Database = serde_json::from_str(&file_content)?;
+        Ok(db)
+    }
+}
+
+struct AppState {
+    db: Mutex<Database>,
+}
+
+#[post("/task", data = "<task>")]
+fn create_task(app_state: &State<AppState>, task: Json<Task>) -> Status {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.insert(task.into_inner());
+    let _ = db.save_to_file();
+    Status::Ok
+}
+
+#[get("/task/<id>")]
+fn read_task(app_state: &State<AppState>, id: u64) -> Option<Json<Task>> {
+    let db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.get(&id).cloned().map(Json)
+}
+
+#[get("/task")]
+fn read_all_tasks(app_state: &State<AppState>) -> Json<Vec<Task>> {
+    let db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    Json(db.get_all().into_iter().cloned().collect())
+}
+
+#[put("/task", data = "<task>")]
+fn update_task(app_state: &State<AppState>, task: Json<Task>) -> Status {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.update(task.into_inner());
+    let _ = db.save_to_file();
+    Status::Ok
+}
+
+#[delete("/task/<id>")]
+fn delete_task(app_state: &State<AppState>, id: u64) -> Status {
+    let mut db: std::sync::MutexGuard<Database> = app_state.db.lock().unwrap();
+    db.delete(&id);
+    let _ = db.save_to_file();
+    Status::Ok
+}
+
+#[get("/")]
+fn index() -> Option<(ContentType, String)> {
+    fs::read_to_string("static/index.html")
+        .ok()
+        .map(|html| (ContentType::HTML, html))
+}
+
+// Answers CORS preflight requests, the Cors fairing adds the headers
+#[options("/<_..>")]
+fn preflight() -> Status {
+    Status::Ok
+}
+
+struct Cors;
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+
+        if origin.starts_with("http://localhost") || origin == "null" {
+            response.set_header(Header::new(
+                "Access-Control-Allow-Origin",
+                origin.to_string(),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                "GET, POST, PUT, DELETE",
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Headers",
+                "Authorization, Accept, Content-Type",
+            ));
+            response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            response.set_header(Header::new("Access-Control-Max-Age", "3600"));
+        }
+    }
+}
+
+#[launch]
+fn rocket() -> _ {
+    let db: Database = match Database::load_from_file() {
+        Ok(db) => db,
+        Err(_) => Database::new(),
+    };
+
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let socket: SocketAddr = address.parse().expect("SERVER_ADDRESS must be host:port");
+    let figment = rocket::Config::figment()
+        .merge(("address", socket.ip()))
+        .merge(("port", socket.port()));
+
+    rocket::custom(figment)
+        .manage(AppState { db: Mutex::new(db) })
+        .attach(Cors)
+        .mount(
+            "/",
+            routes![
+                index,
+                preflight,
+                create_task,
+                read_all_tasks,
+                update_task,
+                read_task,
+                delete_task
+            ],
+        )
+}
@@ -0,0 +1,107 @@
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    get,
+    http::{ContentType, Header, Status},
+    launch, options, routes,
+    serde::json::Json,
+    Request, Response, State,
+};
+
+use serde_json::Value;
+
+use reqwest::Client as HttpClient;
+
+use std::env;
+use std::fs;
+use std::net::SocketAddr;
+
+fn external_api_url() -> String {
+    env::var("EXTERNAL_API_URL").unwrap_or_else(|_| "https://api.example.com".to_string())
+}
+
+struct AppState {
+    http_client: HttpClient,
+    external_api_url: String,
+}
+
+// Forwards GET /external/<resource> to the external API and relays its JSON
+#[get("/external/<resource>")]
+async fn read_external(app_state: &State<AppState>, resource: &str) -> Result<Json<Value>, Status> {
+    let url = format!("{}/{}", app_state.external_api_url, resource);
+
+    let response = app_state
+        .http_client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|_| Status::BadGateway)?;
+
+    let body: Value = response.json().await.map_err(|_| Status::BadGateway)?;
+    Ok(Json(body))
+}
+
+#[get("/")]
+fn index() -> Option<(ContentType, String)> {
+    fs::read_to_string("static/index.html")
+        .ok()
+        .map(|html| (ContentType::HTML, html))
+}
+
+// Answers CORS preflight requests, the Cors fairing adds the headers
+#[options("/<_..>")]
+fn preflight() -> Status {
+    Status::Ok
+}
+
+struct Cors;
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+
+        if origin.starts_with("http://localhost") || origin == "null" {
+            response.set_header(Header::new(
+                "Access-Control-Allow-Origin",
+                origin.to_string(),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                "GET, POST, PUT, DELETE",
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Headers",
+                "Authorization, Accept, Content-Type",
+            ));
+            response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            response.set_header(Header::new("Access-Control-Max-Age", "3600"));
+        }
+    }
+}
+
+#[launch]
+fn rocket() -> _ {
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let socket: SocketAddr = address.parse().expect("SERVER_ADDRESS must be host:port");
+    let figment = rocket::Config::figment()
+        .merge(("address", socket.ip()))
+        .merge(("port", socket.port()));
+
+    rocket::custom(figment)
+        .manage(AppState {
+            http_client: HttpClient::new(),
+            external_api_url: external_api_url(),
+        })
+        .attach(Cors)
+        .mount("/", routes![index, preflight, read_external])
+}
@@ -0,0 +1,87 @@
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    get,
+    http::{ContentType, Header, Status},
+    launch, options, routes,
+    serde::json::Json,
+    Request, Response,
+};
+
+use serde::{Deserialize, Serialize};
+
+use std::env;
+use std::fs;
+use std::net::SocketAddr;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Health {
+    status: String,
+}
+
+#[get("/health")]
+fn health() -> Json<Health> {
+    Json(Health {
+        status: "ok".to_string(),
+    })
+}
+
+#[get("/")]
+fn index() -> Option<(ContentType, String)> {
+    fs::read_to_string("static/index.html")
+        .ok()
+        .map(|html| (ContentType::HTML, html))
+}
+
+// Answers CORS preflight requests, the Cors fairing adds the headers
+#[options("/<_..>")]
+fn preflight() -> Status {
+    Status::Ok
+}
+
+struct Cors;
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+
+        if origin.starts_with("http://localhost") || origin == "null" {
+            response.set_header(Header::new(
+                "Access-Control-Allow-Origin",
+                origin.to_string(),
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Methods",
+                "GET, POST, PUT, DELETE",
+            ));
+            response.set_header(Header::new(
+                "Access-Control-Allow-Headers",
+                "Authorization, Accept, Content-Type",
+            ));
+            response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+            response.set_header(Header::new("Access-Control-Max-Age", "3600"));
+        }
+    }
+}
+
+#[launch]
+fn rocket() -> _ {
+    let address = env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+    let socket: SocketAddr = address.parse().expect("SERVER_ADDRESS must be host:port");
+    let figment = rocket::Config::figment()
+        .merge(("address", socket.ip()))
+        .merge(("port", socket.port()));
+
+    rocket::custom(figment)
+        .attach(Cors)
+        .mount("/", routes![index, preflight, health])
+}
@@ -2,40 +2,100 @@ use ai_functions::ai_function;
 
 #[ai_function]
 pub fn print_backend_webserver_code(_project_description_and_template: &str) {
-    /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_TEMPLATE for a website backend build
+    /// INPUT: Takes in a PROJECT_DESCRIPTION, CODE_TEMPLATE, FRAMEWORK, PERSISTENCE and INSTALLED_LIBRARIES for a website backend build
     /// IMPORTANT: The backend code is ONLY an example. If the Project Description requires it, make as many changes as you like.
     /// IMPORTANT: You do not need to follow the backend code exactly. Write functions that make sense for the users request if required.
     /// FUNCTION: Takes an existing set of code marked as CODE_TEMPLATE and updates or re-writes it to work for the purpose in the PROJECT_DESCRIPTION
-    /// IMPORTANT: The following libraries are already installed
-    ///   reqwest, serde, serde_json, tokio, actix-web, async-trait, actix_cors
+    /// IMPORTANT: The code is written with FRAMEWORK, as the CODE_TEMPLATE is. Only the INSTALLED_LIBRARIES are installed
     /// No other external libraries should be used. Write functions that fit with the description from the PROJECT_DESCRIPTION
-    /// OUTPUT: Print ONLY the code, nothing else. This function ONLY prints code.
+    /// IMPORTANT: If a DATA_MODEL is given, the structs follow it exactly: one struct per entity with the same field names and matching Rust types
+    /// IMPORTANT: Data is stored the way PERSISTENCE describes. If MIGRATIONS are given, queries use exactly those tables and columns
+    /// IMPORTANT: Keep the index route from the CODE_TEMPLATE that serves static/index.html. It is the website frontend.
+    /// OUTPUT: Prints the project as files, each one written as:
+    ///   === FILE: <path> ===
+    ///   <file contents>
+    ///   === END FILE ===
+    /// The files are src/main.rs (app setup and startup), src/models.rs (structs), src/db.rs (storage), src/routes/mod.rs and one src/routes/<resource>.rs per resource with its handlers
+    /// IMPORTANT: Only paths under src/ ending in .rs are allowed. Cargo.toml is managed separately. Print ONLY the files, no commentary
     println!(OUTPUT)
 }
 
 #[ai_function]
 pub fn print_improved_webserver_code(_project_description_and_template: &str) {
-    /// INPUT: Takes in a PROJECT_DESCRIPTION and CODE_TEMPLATE for a website backend build
+    /// INPUT: Takes in a PROJECT_DESCRIPTION, the BACKEND_FILES written so far and INSTALLED_LIBRARIES for a website backend build
     /// FUNCTION: Performs the following tasks:
     ///   1. Removes any bugs in the code and adds minor additional functionality
     ///   2. Makes sure everything requested in the spec from a backend standpoint was followed. If not, add the feature. No code should be implemented later. Everything should be written now.
     ///   3. ONLY writes the code. No commentary.
-    /// IMPORTANT: The following libraries are already installed. Does not use ANY libraries other than what was provided in the template
-    ///   reqwest, serde, serde_json, tokio, actix-web, async-trait
+    ///   4. If the PROJECT_DESCRIPTION contains a data_model, keeps every struct matching its entities and fields exactly.
+    ///   5. If REVIEW_FINDINGS are given, resolves every one of them.
+    ///   6. Keeps the "/" index route that serves static/index.html as the website frontend.
+    ///   7. If an AMEND_GOAL is given, makes that change only. Every route in EXISTING_ROUTES keeps its path and method.
+    /// IMPORTANT: Keeps the web_framework and persistence named in the PROJECT_DESCRIPTION. Does not use ANY libraries other than the INSTALLED_LIBRARIES
+    /// OUTPUT: Prints the project as files, each one written as:
+    ///   === FILE: <path> ===
+    ///   <file contents>
+    ///   === END FILE ===
+    /// Prints every file again, changed or not. The files are src/main.rs (app setup and startup), src/models.rs (structs), src/db.rs (storage), src/routes/mod.rs and one src/routes/<resource>.rs per resource with its handlers
+    /// IMPORTANT: Only paths under src/ ending in .rs are allowed. Cargo.toml is managed separately. Print ONLY the files, no commentary
     println!(OUTPUT)
 }
 
 #[ai_function]
 pub fn print_fixed_code(_broken_code_with_bugs: &str) {
-    /// INPUT: Takes in Rust BROKEN_CODE and the ERROR_BUGS found
-    /// FUNCTION: Removes bugs from code
-    /// IMPORTANT: Only prints out the new and improved code. No commentary or anything else
+    /// INPUT: Takes in the BROKEN_FILES of a Rust project written with FRAMEWORK, the ERROR_BUGS found and the INSTALLED_LIBRARIES
+    /// FUNCTION: Removes bugs from code without switching framework or persistence, or using libraries other than the INSTALLED_LIBRARIES
+    /// OUTPUT: Prints every file of the project again, fixed or not, in the same format as BROKEN_FILES:
+    ///   === FILE: <path> ===
+    ///   <file contents>
+    ///   === END FILE ===
+    /// IMPORTANT: Only paths under src/ ending in .rs are allowed. No commentary or anything else
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_improved_webserver_patches(_project_description_and_files: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION, the BACKEND_FILES written so far and INSTALLED_LIBRARIES for a website backend build
+    /// FUNCTION: Makes the same improvements as a full rewrite would, but only changes the lines that need it:
+    ///   1. Removes any bugs in the code and adds minor additional functionality
+    ///   2. Makes sure everything requested in the spec from a backend standpoint was followed. If not, add the feature.
+    ///   3. If the PROJECT_DESCRIPTION contains a data_model, keeps every struct matching its entities and fields exactly.
+    ///   4. If REVIEW_FINDINGS are given, resolves every one of them.
+    ///   5. If an AMEND_GOAL is given, makes that change only. Every route in EXISTING_ROUTES keeps its path and method.
+    /// IMPORTANT: Keeps the web_framework, persistence and the "/" index route. Does not use ANY libraries other than the INSTALLED_LIBRARIES
+    /// OUTPUT: Prints ONLY search/replace patches, no commentary. Each changed file is written as:
+    ///   === PATCH: <path> ===
+    ///   <<<<<<< SEARCH
+    ///   <lines copied exactly from the file, enough of them to match one place only>
+    ///   =======
+    ///   <the lines that replace them>
+    ///   >>>>>>> REPLACE
+    ///   === END PATCH ===
+    /// A file can have several SEARCH/REPLACE blocks. A new file has an empty SEARCH block. Files that do not change are left out
+    /// IMPORTANT: Only paths under src/ ending in .rs are patched. Cargo.toml is managed separately
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_fixed_code_patches(_broken_files_with_bugs: &str) {
+    /// INPUT: Takes in the BROKEN_FILES of a Rust project written with FRAMEWORK, the ERROR_BUGS found and the INSTALLED_LIBRARIES
+    /// FUNCTION: Removes bugs from code without switching framework or persistence, or using libraries other than the INSTALLED_LIBRARIES. Only changes the lines that cause the ERROR_BUGS
+    /// OUTPUT: Prints ONLY search/replace patches, no commentary. Each changed file is written as:
+    ///   === PATCH: <path> ===
+    ///   <<<<<<< SEARCH
+    ///   <lines copied exactly from the file, enough of them to match one place only>
+    ///   =======
+    ///   <the lines that replace them>
+    ///   >>>>>>> REPLACE
+    ///   === END PATCH ===
+    /// A file can have several SEARCH/REPLACE blocks. A new file has an empty SEARCH block. Files that do not change are left out
+    /// IMPORTANT: Only paths under src/ ending in .rs are patched. Cargo.toml is managed separately
     println!(OUTPUT)
 }
 
 #[ai_function]
 pub fn print_rest_api_endpoints(_code_input: &str) {
-    /// INPUT: Takes in Rust webserver CODE_INPUT based on actix-web
+    /// INPUT: Takes in Rust webserver CODE_INPUT based on FRAMEWORK (actix, axum or rocket). It may be split over several files, each starting with "=== FILE: <path> ==="
     /// FUNCTION: Prints out the JSON schema for url endpoints and their respective types
     /// LOGIC: Script analyses all code and can categorize into the following object keys:
     ///   "route": This represents the url path of the endpoint
@@ -43,6 +103,9 @@ pub fn print_rest_api_endpoints(_code_input: &str) {
     ///   "method": This represents the method being called
     ///   "request_body": This represents the body of a post method request
     ///   "response": This represents the output based upon the structs in the code and understanding the functions
+    /// IMPORTANT: Skip the "/" index route that serves the static frontend page
+    /// IMPORTANT: Write dynamic segments as {id} whatever the framework syntax is, so rocket "<id>" and axum ":id" both become "{id}"
+    /// IMPORTANT: Include the prefix of any actix scope, axum nest or rocket mount in the route
     /// IMPORTANT: Only prints out the JSON schema. No commentary or anything else.
     /// MUST READ: All keys are strings. Even bool should be wrapped in double quotes as "bool"
     /// EXAMPLE:
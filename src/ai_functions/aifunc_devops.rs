@@ -0,0 +1,50 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_dockerfile(_backend_code_and_project_description: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver crate named web_template, its PROJECT_DESCRIPTION, its PERSISTENCE (json_file, sqlite or postgres) and HAS_MIGRATIONS
+    /// FUNCTION: Writes a multi-stage Dockerfile for the crate
+    /// IMPORTANT: The first stage builds the release binary with the official rust image, the last stage copies ONLY the binary into a slim debian image
+    /// IMPORTANT: When HAS_MIGRATIONS is true, the first stage copies the migrations/ folder before cargo build, sqlx::migrate!() embeds it when the binary compiles
+    /// IMPORTANT: The final stage sets WORKDIR /app, copies the static/ folder next to the binary, sets SERVER_ADDRESS=0.0.0.0:8080, exposes 8080 and runs the binary
+    /// OUTPUT: Print ONLY the Dockerfile, nothing else. No markdown.
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_docker_compose(_backend_code_and_project_description: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver crate named web_template, its PROJECT_DESCRIPTION and its PERSISTENCE (json_file, sqlite or postgres)
+    /// FUNCTION: Writes a docker-compose.yml that builds the Dockerfile in the same folder and runs the webserver
+    /// IMPORTANT: Maps port 8080:8080, loads variables from .env, and mounts a named volume at /app/data. Named volumes are declared under the top level volumes key
+    /// IMPORTANT: For json_file, sets DATABASE_PATH=/app/data/database.json so the JSON database survives container restarts
+    /// IMPORTANT: For sqlite, sets DATABASE_URL=sqlite:///app/data/database.db?mode=rwc so the database survives container restarts
+    /// IMPORTANT: For postgres, adds a db service with the postgres:16 image, POSTGRES_USER=postgres, POSTGRES_PASSWORD=postgres, POSTGRES_DB=web_template and a named volume at /var/lib/postgresql/data. The webserver depends_on db and sets DATABASE_URL=postgres://postgres:postgres@db:5432/web_template
+    /// OUTPUT: Print ONLY the YAML, nothing else. No markdown.
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_env_example(_backend_code_and_project_description: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver, its PROJECT_DESCRIPTION and its PERSISTENCE (json_file, sqlite or postgres)
+    /// FUNCTION: Writes a .env.example listing every environment variable the BACKEND_FILES reads with env::var, with a safe example value
+    /// IMPORTANT: Always includes SERVER_ADDRESS, and DATABASE_PATH for json_file or DATABASE_URL for sqlite and postgres. One KEY=value per line, keys in UPPER_SNAKE_CASE, comments start with #
+    /// OUTPUT: Print ONLY the file, nothing else. No markdown.
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_makefile(_backend_code_and_project_description: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver crate and its PROJECT_DESCRIPTION
+    /// FUNCTION: Writes a Makefile with the targets build, run, test, docker-build and docker-up, declared as .PHONY
+    /// IMPORTANT: build, run and test use cargo. docker-build and docker-up use docker compose. Recipe lines are indented with a TAB character
+    /// OUTPUT: Print ONLY the Makefile, nothing else. No markdown.
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_fixed_devops_file(_file_name_content_and_lint_errors: &str) {
+    /// INPUT: Takes in a FILE_NAME, its FILE_CONTENT and the LINT_ERRORS found in it
+    /// FUNCTION: Fixes every lint error while keeping the rest of the file as it is
+    /// OUTPUT: Print ONLY the fixed file, nothing else. No markdown.
+    println!(OUTPUT)
+}
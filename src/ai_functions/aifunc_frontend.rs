@@ -0,0 +1,22 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_frontend_webpage_code(_project_description_and_endpoints: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION and the API_ENDPOINT_SCHEMA of an existing website backend
+    /// FUNCTION: Writes a single page frontend for the backend as ONE static HTML file with inline CSS and vanilla JavaScript
+    /// IMPORTANT: The page is served by the backend itself at the "/" route, so all requests use relative urls such as fetch("/task")
+    /// IMPORTANT: Every route in the API_ENDPOINT_SCHEMA must be callable from the page. Dynamic segments such as {id} are filled from user input or list items
+    /// IMPORTANT: Request bodies are sent as JSON with the "Content-Type: application/json" header and must match the request_body of the route
+    /// IMPORTANT: No external libraries, frameworks, CDNs or build steps. Everything lives in the one HTML file
+    /// OUTPUT: Print ONLY the HTML document starting with <!DOCTYPE html>, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_fixed_frontend_code(_frontend_code_and_missing_routes: &str) {
+    /// INPUT: Takes in FRONTEND_CODE for a single page website and the MISSING_ROUTES it does not call yet
+    /// FUNCTION: Updates the FRONTEND_CODE so that every route in MISSING_ROUTES can be called from the page, keeping all existing functionality
+    /// IMPORTANT: All requests use relative urls and JSON bodies. No external libraries, frameworks or CDNs
+    /// OUTPUT: Print ONLY the full HTML document starting with <!DOCTYPE html>, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
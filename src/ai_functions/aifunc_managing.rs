@@ -13,3 +13,62 @@ pub fn convert_user_input_to_goal(_user_request: &str) {
     ///   OUTPUT = "build a website that fetches and stores crypto price data within a supabase setup including a frontend UI to fetch the data."
     println!(OUTPUT)
 }
+
+#[ai_function]
+pub fn convert_amend_request_to_goal(_project_and_user_request: &str) {
+    /// Input: Takes in the PROJECT_DESCRIPTION of a website that is already built, its API_ENDPOINT_SCHEMA and a USER_REQUEST for a change to it
+    /// Function: Works out the change the user asks for, and only that change. The existing endpoints stay as they are
+    /// Output: Prints an object response in the following format:
+    ///   {
+    ///     "change_goal": string, // the change only, starting with "change the website so that ..."
+    ///     "project_description": string, // the PROJECT_DESCRIPTION updated with the change, starting with "build a website that ..."
+    ///     "is_scope_change_required": bool // true if the change needs CRUD, user login or external urls the PROJECT_DESCRIPTION did not need before
+    ///   }
+    /// Example:
+    ///   PROJECT_DESCRIPTION = "build a website that lets users track their daily tasks"
+    ///   USER_REQUEST = "add pagination to tasks"
+    ///   prints:
+    ///   {
+    ///     "change_goal": "change the website so that listing tasks accepts page and page_size query parameters",
+    ///     "project_description": "build a website that lets users track their daily tasks, listing them a page at a time",
+    ///     "is_scope_change_required": false
+    ///   }
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_imported_project_summary(_routes_and_code: &str) {
+    /// Input: Takes in the CODE_INPUT of an existing Rust web server written with FRAMEWORK and the API_ENDPOINT_SCHEMA read from it
+    /// Function: Summarises what the web server does and works out its scope, as if it had been requested as a new build
+    /// Output: Prints an object response in the following format:
+    ///   {
+    ///     "project_description": string, // short summary starting with "build a website that ..."
+    ///     "project_scope": {
+    ///       "is_crud_required": bool, // true if the server stores and manages records
+    ///       "is_user_login_and_logout": bool, // true if users can log in and log out
+    ///       "is_external_urls_required": bool // true if it fetches data from third party providers
+    ///     }
+    ///   }
+    println!(OUTPUT)
+}
+
+#[ai_function]
+pub fn print_replan_decision(_failure_and_factsheet: &str) {
+    /// Input: Takes in a FAILURE_REPORT of agents that gave up building a website, the PROJECT_DESCRIPTION, PROJECT_SCOPE, EXTERNAL_URLS, FRAMEWORK and CODE_TEMPLATE they worked from, and the PREVIOUS_REPLANS already tried
+    /// Function: Decides on one change to the plan that gives the agents a better chance when they run again. Never repeats a previous replan
+    /// Important: Simplifying the scope drops features, so prefer other urls or another template when they can fix the failure
+    /// Output: Prints an object response in one of the following formats:
+    ///   {"action": "simplify_scope", "reason": string, "project_scope": {"is_crud_required": bool, "is_user_login_and_logout": bool, "is_external_urls_required": bool}}
+    ///   {"action": "swap_external_urls", "reason": string, "external_urls": ["url1", "url2", ...]} // public endpoints that need no API keys
+    ///   {"action": "change_template", "reason": string, "template": "crud" | "crud_auth" | "external_api_proxy" | "minimal"}
+    ///   {"action": "give_up", "reason": string} // when no change to the plan can help
+    /// Example:
+    ///   FAILURE_REPORT = "solutions architect: Every external url is dead: https://api.deadexchange.io/prices"
+    ///   prints:
+    ///   {
+    ///     "action": "swap_external_urls",
+    ///     "reason": "the exchange api is gone, coingecko serves the same prices without a key",
+    ///     "external_urls": ["https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies=usd"]
+    ///   }
+    println!(OUTPUT)
+}
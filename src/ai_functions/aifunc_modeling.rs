@@ -0,0 +1,30 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_data_model(_project_description_and_scope: &str) {
+    /// INPUT: Takes in a PROJECT_DESCRIPTION and PROJECT_SCOPE for a website backend, and optionally VALIDATION_ERRORS from a previous attempt
+    /// FUNCTION: Designs the entities the backend stores, with their fields, field types and relations between entities
+    /// IMPORTANT: Every entity has an "id" field of type "integer". Entity names are PascalCase, field names are snake_case
+    /// IMPORTANT: If PROJECT_SCOPE.is_user_login_and_logout is true there is a "User" entity with "username" and "password" fields
+    /// IMPORTANT: field_type is one of "string", "integer", "float", "boolean", "date_time", "uuid"
+    /// IMPORTANT: relation kind is one of "one_to_one", "one_to_many", "many_to_one", "many_to_many". "target" names another entity and "via_field" names the field holding the link
+    /// IMPORTANT: If VALIDATION_ERRORS are given, fixes every one of them
+    /// OUTPUT: Prints ONLY a JSON object in the following format. No commentary.
+    /// {
+    ///   "entities": [
+    ///     {
+    ///       "name": "Task",
+    ///       "fields": [
+    ///         { "name": "id", "field_type": "integer", "is_optional": false },
+    ///         { "name": "title", "field_type": "string", "is_optional": false },
+    ///         { "name": "user_id", "field_type": "integer", "is_optional": false }
+    ///       ],
+    ///       "relations": [
+    ///         { "kind": "many_to_one", "target": "User", "via_field": "user_id" }
+    ///       ]
+    ///     },
+    ///     ... // etc
+    ///   ]
+    /// }
+    println!(OUTPUT)
+}
@@ -0,0 +1,26 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_code_review(_backend_code_and_factsheet: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver (actix-web, axum or rocket) and the FACTSHEET describing the project it was built for
+    /// FUNCTION: Reviews the BACKEND_FILES against the FACTSHEET like a strict senior Rust reviewer and lists every problem found
+    /// CHECKS: At minimum the review looks for:
+    ///   "missing_route": a route that the project description or project scope implies but the code does not register
+    ///   "plaintext_password": passwords stored or compared as plain text instead of being hashed
+    ///   "mutex_contention": a Mutex held across slow work such as file writes or external http calls, or one global lock for everything
+    ///   "error_handling": unwrap, expect or ignored Results (let _ = ...) on fallible operations inside request handlers
+    ///   "other": any other bug or gap that would stop the website from working as described
+    /// SEVERITY: "blocking" when the website would not meet the project description or would be unsafe for users, "warning" for risky code, "info" for style
+    /// IMPORTANT: Only prints out the JSON list. No commentary or anything else. Prints [] when nothing is wrong.
+    /// OUTPUT: Prints a list of findings in the following format:
+    /// [
+    ///   {
+    ///     "severity": "blocking",
+    ///     "category": "plaintext_password",
+    ///     "description": "User.password is saved to database.json and compared as plain text in login",
+    ///     "suggestion": "Hash passwords with argon2 before storing them and verify the hash in login"
+    ///   },
+    ///   ... // etc
+    /// ]
+    println!(OUTPUT)
+}
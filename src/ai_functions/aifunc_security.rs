@@ -0,0 +1,24 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_security_audit(_backend_code: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a Rust webserver (actix-web, axum or rocket) that lets users sign up, log in and log out
+    /// FUNCTION: Audits the BACKEND_FILES for authentication and input handling weaknesses and prints one result per check
+    /// CHECKS: Prints exactly these checks:
+    ///   "password_hashing": passes only if passwords are hashed with a salted algorithm before being stored and are never compared as plain text
+    ///   "session_handling": passes only if a successful login issues a session or token that later requests verify, and logout invalidates it
+    ///   "cors": passes only if CORS allows a fixed list of trusted origins. Cors::permissive() or allowing any origin fails
+    ///   "input_validation": passes only if request bodies and path parameters are validated and bad input gets a 4xx response instead of a panic
+    ///   "path_traversal": passes only if the JSON database file name is a constant and no user input is ever used to build a file path
+    /// IMPORTANT: Only prints out the JSON list. No commentary or anything else.
+    /// OUTPUT: Prints a list in the following format:
+    /// [
+    ///   {
+    ///     "check": "password_hashing",
+    ///     "passed": false,
+    ///     "details": "register stores User.password as given and login compares it with =="
+    ///   },
+    ///   ... // etc
+    /// ]
+    println!(OUTPUT)
+}
@@ -0,0 +1,15 @@
+use ai_functions::ai_function;
+
+#[ai_function]
+pub fn print_backend_integration_tests(_backend_code_and_endpoints: &str) {
+    /// INPUT: Takes in the BACKEND_FILES of a webserver written with FRAMEWORK, its API_ENDPOINT_SCHEMA, IS_USER_LOGIN_AND_LOGOUT, TESTING_STYLE, PERSISTENCE and INSTALLED_LIBRARIES
+    /// FUNCTION: Writes Rust integration tests for every route in the API_ENDPOINT_SCHEMA, driving the app in process as TESTING_STYLE describes
+    /// IMPORTANT: The tests are saved as the file src/api_tests.rs, which main.rs declares as "#[cfg(test)] mod api_tests;"
+    ///   So the file starts with "use super::*;" and reaches structs, handlers and AppState in other BACKEND_FILES modules through crate:: paths
+    /// IMPORTANT: Each test builds its own app with the same routes as main and fresh empty storage, set up the way PERSISTENCE describes
+    /// IMPORTANT: For every CRUD resource write one round trip test: create, read it back, update it, read the update, delete it, confirm it is gone
+    /// IMPORTANT: If IS_USER_LOGIN_AND_LOGOUT is true, also test signing up a user then logging in with the right and the wrong password
+    /// IMPORTANT: Tests follow TESTING_STYLE. Only the INSTALLED_LIBRARIES are available, no other external libraries should be used
+    /// OUTPUT: Print ONLY the code of src/api_tests.rs, nothing else. This function ONLY prints code.
+    println!(OUTPUT)
+}
@@ -1,3 +1,9 @@
 pub mod aifunc_architect;
 pub mod aifunc_backend;
+pub mod aifunc_devops;
+pub mod aifunc_frontend;
 pub mod aifunc_managing;
+pub mod aifunc_modeling;
+pub mod aifunc_reviewing;
+pub mod aifunc_security;
+pub mod aifunc_testing;
@@ -1,4 +1,7 @@
-use std::env::{self, consts::OS};
+use std::{
+    env::{self, consts::OS},
+    future::Future,
+};
 
 use dotenv::dotenv;
 use reqwest::{
@@ -6,34 +9,135 @@ use reqwest::{
     Client,
 };
 
-use crate::models::general::llm::{ApiResponse, ChatCompletion, Message};
+use crate::models::general::llm::{
+    AnthropicError, AnthropicRequest, AnthropicResponse, ApiResponse, ChatCompletion, LlmProvider,
+    Message, ModelChoice, ModelConfig, DEFAULT_ANTHROPIC_MAX_TOKENS,
+};
 
-// Call Large Language Model
-pub async fn call_gpt(messages: Vec<Message>) -> Result<String, Box<dyn std::error::Error + Send>> {
-    dotenv().ok();
+const OPENAI_URL: &str = "https://api.openai.com/v1/chat/completions";
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434/v1/chat/completions";
 
-    // Extract API keys
-    let api_key = env::var("OPEN_AI_KEY").expect("OPEN_AI_KEY not found in environment variables");
+tokio::task_local! {
+    static MODEL_CONFIG: ModelConfig;
+}
 
-    let url = "https://api.openai.com/v1/chat/completions";
+// Makes every LLM call the future makes use the given model settings
+pub async fn with_model_config<F: Future>(config: ModelConfig, future: F) -> F::Output {
+    MODEL_CONFIG.scope(config, future).await
+}
+
+// The model an ai_function is called with where it runs now, the built-in default outside an agent
+pub fn model_choice(ai_function: &str) -> ModelChoice {
+    MODEL_CONFIG
+        .try_with(|config| config.resolve(ai_function))
+        .unwrap_or_else(|_| ModelConfig::default().resolve(ai_function))
+}
+
+// Call Large Language Model
+pub async fn call_gpt(
+    messages: Vec<Message>,
+    choice: &ModelChoice,
+) -> Result<String, Box<dyn std::error::Error + Send>> {
+    dotenv().ok();
 
     let mut headers = HeaderMap::new();
 
-    headers.insert(
-        "Authorization",
-        HeaderValue::from_str(&format!("Bearer {}", api_key))
-            .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?,
-    );
+    // Extract API keys
+    let url = match choice.provider {
+        LlmProvider::OpenAi => {
+            let api_key =
+                env::var("OPEN_AI_KEY").expect("OPEN_AI_KEY not found in environment variables");
+            headers.insert(
+                "Authorization",
+                HeaderValue::from_str(&format!("Bearer {}", api_key))
+                    .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?,
+            );
+            OPENAI_URL.to_string()
+        }
+        LlmProvider::Anthropic => {
+            let api_key = env::var("ANTHROPIC_API_KEY")
+                .expect("ANTHROPIC_API_KEY not found in environment variables");
+            headers.insert(
+                "x-api-key",
+                HeaderValue::from_str(&api_key)
+                    .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?,
+            );
+            headers.insert(
+                "anthropic-version",
+                HeaderValue::from_static(ANTHROPIC_VERSION),
+            );
+            ANTHROPIC_URL.to_string()
+        }
+        LlmProvider::Ollama => {
+            env::var("OLLAMA_URL").unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string())
+        }
+    };
 
     let client = Client::builder()
         .default_headers(headers)
         .build()
         .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?;
 
+    if choice.provider == LlmProvider::Anthropic {
+        // The messages API keeps system prompts apart, the function printer prompt works as
+        // the user turn
+        let messages = messages
+            .into_iter()
+            .map(|message| Message {
+                role: if message.role == "assistant" {
+                    message.role
+                } else {
+                    "user".to_string()
+                },
+                content: message.content,
+            })
+            .collect();
+        let request = AnthropicRequest {
+            model: choice.model.clone(),
+            messages,
+            temperature: choice.temperature,
+            max_tokens: choice.max_tokens.unwrap_or(DEFAULT_ANTHROPIC_MAX_TOKENS),
+        };
+
+        let response = client
+            .post(url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?;
+
+        // Failed calls answer with an error body, not content blocks
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = match serde_json::from_str::<AnthropicError>(&body) {
+                Ok(error) => format!("{}: {}", error.error.kind, error.error.message),
+                Err(_) => body,
+            };
+            let error: Box<dyn std::error::Error + Send + Sync> =
+                format!("Anthropic API call failed with {}: {}", status, message).into();
+            return Err(error);
+        }
+
+        let response: AnthropicResponse = response
+            .json()
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?;
+
+        return Ok(response
+            .content
+            .into_iter()
+            .map(|content| content.text)
+            .collect());
+    }
+
     let chat_completion = ChatCompletion {
-        model: "gpt-4o".to_string(),
+        model: choice.model.clone(),
         messages,
-        temperature: 0.1,
+        temperature: choice.temperature,
+        max_tokens: choice.max_tokens,
     };
 
     let response: ApiResponse = client
@@ -62,7 +166,7 @@ mod tests {
 
         let messages = vec![message];
 
-        let result = call_gpt(messages).await;
+        let result = call_gpt(messages, &model_choice("tests_call_to_openai")).await;
 
         if let Ok(result_str) = result {
             dbg!(result_str);
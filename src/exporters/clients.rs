@@ -0,0 +1,719 @@
+use std::collections::HashSet;
+
+use crate::{
+    helpers::web_server::{save_web_server_file, WEB_SERVER_URL},
+    models::{
+        agents::agent_traits::{Entity, FactSheet, FieldType, RouteObject},
+        general::schema::{HttpMethod, TypeSchema},
+    },
+};
+
+use super::{find_matching_entity, operation_name};
+
+pub const RUST_CLIENT_PATH: &str = "clients/rust";
+const TYPESCRIPT_CLIENT_PATH: &str = "clients/typescript";
+pub const RUST_CLIENT_SMOKE_EXAMPLE: &str = "smoke";
+
+const GENERATED_HEADER: &str =
+    "// Generated from the extracted API endpoints, do not edit by hand\n";
+
+const RUST_CLIENT_MANIFEST: &str = r#"[package]
+name = "web_template_client"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+
+[dependencies]
+reqwest = { version = "0.12.15", features = ["json"] }
+serde = { version = "1.0.219", features = ["derive"] }
+serde_json = "1.0.140"
+
+[dev-dependencies]
+tokio = { version = "1.44.2", features = ["full"] }
+"#;
+
+const RUST_KEYWORDS: [&str; 47] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+const TYPESCRIPT_RESERVED: [&str; 12] = [
+    "case", "class", "default", "delete", "function", "in", "new", "switch", "this", "var", "void",
+    "with",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClientType {
+    String,
+    Integer,
+    Float,
+    Number,
+    Bool,
+    Any,
+    Array(Box<ClientType>),
+    Named(String),
+}
+
+#[derive(Debug)]
+struct ClientField {
+    name: String,
+    client_type: ClientType,
+    is_optional: bool,
+}
+
+#[derive(Debug)]
+struct ClientStruct {
+    name: String,
+    fields: Vec<ClientField>,
+}
+
+#[derive(Debug)]
+struct ClientEndpoint {
+    name: String,
+    method: HttpMethod,
+    route: String,
+    path_params: Vec<String>,
+    request: Option<ClientType>,
+    response: Option<ClientType>,
+}
+
+// Language neutral view of the API that both client renderers read from. Data model entities
+// become named types, every other object body gets a struct named after its route
+#[derive(Debug, Default)]
+struct ClientModel {
+    structs: Vec<ClientStruct>,
+    endpoints: Vec<ClientEndpoint>,
+    used_names: HashSet<String>,
+}
+
+impl ClientModel {
+    fn new(routes: &[RouteObject], entities: &[Entity]) -> Self {
+        let mut model = Self::default();
+
+        for entity in entities {
+            model.add_entity(entity);
+        }
+
+        // "/" serves the frontend page rather than JSON
+        for route in routes.iter().filter(|route| route.route != "/") {
+            let name = operation_name(route);
+            let type_name = pascal_case(&name);
+
+            let request = route
+                .request_body
+                .as_ref()
+                .map(|body| model.client_type(body, &format!("{}Request", type_name), entities));
+            let response = route.response.as_ref().map(|response| {
+                model.client_type(response, &format!("{}Response", type_name), entities)
+            });
+
+            model.endpoints.push(ClientEndpoint {
+                name,
+                method: route.method,
+                route: route.route.clone(),
+                path_params: route.path_params.clone(),
+                request,
+                response,
+            });
+        }
+
+        model
+    }
+
+    fn add_entity(&mut self, entity: &Entity) {
+        let name = self.unique_name(&pascal_case(&entity.name));
+
+        let fields = entity
+            .fields
+            .iter()
+            .map(|field| ClientField {
+                name: field.name.clone(),
+                client_type: match field.field_type {
+                    FieldType::Integer => ClientType::Integer,
+                    FieldType::Float => ClientType::Float,
+                    FieldType::Boolean => ClientType::Bool,
+                    FieldType::String | FieldType::DateTime | FieldType::Uuid => ClientType::String,
+                },
+                is_optional: field.is_optional,
+            })
+            .collect();
+
+        self.structs.push(ClientStruct { name, fields });
+    }
+
+    fn client_type(
+        &mut self,
+        schema: &TypeSchema,
+        type_name: &str,
+        entities: &[Entity],
+    ) -> ClientType {
+        match schema {
+            TypeSchema::String => ClientType::String,
+            TypeSchema::Number => ClientType::Number,
+            TypeSchema::Bool => ClientType::Bool,
+            TypeSchema::Unknown => ClientType::Any,
+            TypeSchema::Array(item) => ClientType::Array(Box::new(self.client_type(
+                item,
+                &format!("{}Item", type_name),
+                entities,
+            ))),
+            TypeSchema::Object(fields) => {
+                if let Some(entity) = find_matching_entity(fields, entities) {
+                    return ClientType::Named(pascal_case(&entity.name));
+                }
+
+                let name = self.unique_name(type_name);
+                let fields = fields
+                    .iter()
+                    .map(|(field_name, field)| ClientField {
+                        name: field_name.clone(),
+                        client_type: self.client_type(
+                            field,
+                            &format!("{}{}", name, pascal_case(field_name)),
+                            entities,
+                        ),
+                        is_optional: false,
+                    })
+                    .collect();
+
+                self.structs.push(ClientStruct {
+                    name: name.clone(),
+                    fields,
+                });
+                ClientType::Named(name)
+            }
+        }
+    }
+
+    fn unique_name(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 2;
+
+        while !self.used_names.insert(name.clone()) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+
+        name
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or(String::new(), |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_is_lower = false;
+
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && previous_is_lower {
+            snake.push('_');
+        }
+
+        if c.is_ascii_alphanumeric() {
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.ends_with('_') {
+            snake.push('_');
+        }
+
+        previous_is_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+
+    let snake = snake.trim_matches('_').to_string();
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", snake)
+    } else {
+        snake
+    }
+}
+
+// Field or parameter identifier, raw when it clashes with a keyword
+fn rust_ident(name: &str) -> String {
+    let ident = snake_case(name);
+
+    match ident.as_str() {
+        "self" | "super" | "crate" => format!("{}_", ident),
+        keyword if RUST_KEYWORDS.contains(&keyword) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+fn typescript_ident(name: &str) -> String {
+    let snake = snake_case(name);
+    let camel: String = snake
+        .split('_')
+        .enumerate()
+        .map(|(index, part)| match index {
+            0 => part.to_string(),
+            _ => pascal_case(part),
+        })
+        .collect();
+
+    if TYPESCRIPT_RESERVED.contains(&camel.as_str()) || camel.is_empty() {
+        format!("{}Param", camel)
+    } else {
+        camel
+    }
+}
+
+fn rust_type(client_type: &ClientType) -> String {
+    match client_type {
+        ClientType::String => "String".to_string(),
+        ClientType::Integer => "i64".to_string(),
+        ClientType::Float => "f64".to_string(),
+        ClientType::Number => "serde_json::Number".to_string(),
+        ClientType::Bool => "bool".to_string(),
+        ClientType::Any => "serde_json::Value".to_string(),
+        ClientType::Array(item) => format!("Vec<{}>", rust_type(item)),
+        ClientType::Named(name) => name.clone(),
+    }
+}
+
+fn typescript_type(client_type: &ClientType) -> String {
+    match client_type {
+        ClientType::String => "string".to_string(),
+        ClientType::Integer | ClientType::Float | ClientType::Number => "number".to_string(),
+        ClientType::Bool => "boolean".to_string(),
+        ClientType::Any => "unknown".to_string(),
+        ClientType::Array(item) => format!("{}[]", typescript_type(item)),
+        ClientType::Named(name) => name.clone(),
+    }
+}
+
+// Splits /task/{id} into literal text and path parameter slots
+fn route_segments(route: &str) -> Vec<Option<&str>> {
+    route
+        .split('/')
+        .skip(1)
+        .map(|segment| match segment.starts_with('{') {
+            true => None,
+            false => Some(segment),
+        })
+        .collect()
+}
+
+fn render_rust_client(model: &ClientModel) -> String {
+    let mut code = format!(
+        "{}\nuse serde::{{Deserialize, Serialize}};\n\n",
+        GENERATED_HEADER
+    );
+
+    for client_struct in &model.structs {
+        code.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+        code.push_str(&format!("pub struct {} {{\n", client_struct.name));
+
+        for field in &client_struct.fields {
+            let ident = rust_ident(&field.name);
+            if ident.trim_start_matches("r#") != field.name {
+                code.push_str(&format!("    #[serde(rename = {:?})]\n", field.name));
+            }
+
+            let field_type = rust_type(&field.client_type);
+            if field.is_optional {
+                code.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+                code.push_str(&format!("    pub {}: Option<{}>,\n", ident, field_type));
+            } else {
+                code.push_str(&format!("    pub {}: {},\n", ident, field_type));
+            }
+        }
+
+        code.push_str("}\n\n");
+    }
+
+    code.push_str(
+        "#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+",
+    );
+
+    for endpoint in &model.endpoints {
+        let mut params: Vec<String> = endpoint
+            .path_params
+            .iter()
+            .map(|param| format!("{}: impl std::fmt::Display", rust_ident(param)))
+            .collect();
+
+        if let Some(request) = &endpoint.request {
+            let body_type = match request {
+                ClientType::Array(item) => format!("[{}]", rust_type(item)),
+                other => rust_type(other),
+            };
+            params.push(format!("body: &{}", body_type));
+        }
+
+        let return_type = endpoint
+            .response
+            .as_ref()
+            .map_or("()".to_string(), rust_type);
+
+        let path: String = route_segments(&endpoint.route)
+            .iter()
+            .map(|segment| format!("/{}", segment.unwrap_or("{}")))
+            .collect();
+        let url_args: String = endpoint
+            .path_params
+            .iter()
+            .map(|param| format!(", {}", rust_ident(param)))
+            .collect();
+
+        code.push_str(&format!(
+            "\n    pub async fn {}(&self{}) -> Result<{}, reqwest::Error> {{\n",
+            endpoint.name,
+            params
+                .iter()
+                .map(|param| format!(", {}", param))
+                .collect::<String>(),
+            return_type
+        ));
+        code.push_str(&format!(
+            "        let url = format!(\"{{}}{}\", self.base_url{});\n",
+            path, url_args
+        ));
+
+        let body = match endpoint.request {
+            Some(_) => ".json(body)",
+            None => "",
+        };
+        // reqwest::Client has no options() shorthand
+        let builder = match endpoint.method {
+            HttpMethod::Options => "request(reqwest::Method::OPTIONS, url)".to_string(),
+            method => format!("{}(url)", method),
+        };
+        let request = format!(
+            "self.http.{}{}.send().await?.error_for_status()?",
+            builder, body
+        );
+
+        match endpoint.response {
+            Some(_) => code.push_str(&format!("        {}.json().await\n", request)),
+            None => code.push_str(&format!("        {};\n        Ok(())\n", request)),
+        }
+        code.push_str("    }\n");
+    }
+
+    code.push_str("}\n");
+    code
+}
+
+// Calls every GET route that needs no path parameters, so a failing call points at a route
+// whose response does not match the extracted schema. 401 and 403 count as answered
+fn render_rust_smoke_example(model: &ClientModel) -> String {
+    let calls: Vec<&ClientEndpoint> = model
+        .endpoints
+        .iter()
+        .filter(|endpoint| endpoint.method == HttpMethod::Get && endpoint.path_params.is_empty())
+        .collect();
+
+    let client_binding = match calls.is_empty() {
+        true => "_client",
+        false => "client",
+    };
+
+    let mut code = format!(
+        "{}\nuse web_template_client::ApiClient;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {{
+    let base_url = std::env::var(\"API_BASE_URL\").unwrap_or_else(|_| {:?}.to_string());
+    let {} = ApiClient::new(base_url);
+",
+        GENERATED_HEADER, WEB_SERVER_URL, client_binding
+    );
+
+    // A route that wants a login still answered, only other failures count against it
+    for endpoint in &calls {
+        let ok_arm = match endpoint.response {
+            Some(_) => format!(
+                "Ok({name}) => println!(\"{name}: {{:?}}\", {name})",
+                name = endpoint.name
+            ),
+            None => format!("Ok(()) => println!(\"{}: ok\")", endpoint.name),
+        };
+        code.push_str(&format!(
+            "\n    match client.{name}().await {{\n        {ok_arm},\n        Err(error) if is_auth_required(&error) => println!(\"{name}: needs authentication\"),\n        Err(error) => return Err(error.into()),\n    }}\n",
+            name = endpoint.name,
+            ok_arm = ok_arm
+        ));
+    }
+
+    code.push_str("\n    Ok(())\n}\n");
+    if !calls.is_empty() {
+        code.push_str(
+            "\nfn is_auth_required(error: &reqwest::Error) -> bool {
+    matches!(
+        error.status(),
+        Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)
+    )
+}\n",
+        );
+    }
+    code
+}
+
+fn typescript_property(name: &str) -> String {
+    let is_ident = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    match is_ident {
+        true => name.to_string(),
+        false => format!("{:?}", name),
+    }
+}
+
+fn render_typescript_client(model: &ClientModel) -> String {
+    let mut code = format!("{}\n", GENERATED_HEADER);
+
+    for client_struct in &model.structs {
+        code.push_str(&format!("export interface {} {{\n", client_struct.name));
+        for field in &client_struct.fields {
+            code.push_str(&format!(
+                "  {}{}: {};\n",
+                typescript_property(&field.name),
+                if field.is_optional { "?" } else { "" },
+                typescript_type(&field.client_type)
+            ));
+        }
+        code.push_str("}\n\n");
+    }
+
+    code.push_str(&format!(
+        "export class ApiError extends Error {{
+  constructor(public readonly status: number, public readonly body: string) {{
+    super(`Request failed with status ${{status}}`);
+  }}
+}}
+
+export class ApiClient {{
+  private readonly baseUrl: string;
+
+  constructor(baseUrl: string = {:?}) {{
+    this.baseUrl = baseUrl.replace(/\\/+$/, \"\");
+  }}
+
+  private async request<T>(method: string, path: string, body?: unknown): Promise<T> {{
+    const response = await fetch(`${{this.baseUrl}}${{path}}`, {{
+      method,
+      headers: body === undefined ? undefined : {{ \"Content-Type\": \"application/json\" }},
+      body: body === undefined ? undefined : JSON.stringify(body),
+    }});
+    const text = await response.text();
+    if (!response.ok) {{
+      throw new ApiError(response.status, text);
+    }}
+    return (text ? JSON.parse(text) : undefined) as T;
+  }}
+",
+        WEB_SERVER_URL
+    ));
+
+    for endpoint in &model.endpoints {
+        let mut params: Vec<String> = endpoint
+            .path_params
+            .iter()
+            .map(|param| format!("{}: string | number", typescript_ident(param)))
+            .collect();
+
+        if let Some(request) = &endpoint.request {
+            params.push(format!("body: {}", typescript_type(request)));
+        }
+
+        let return_type = endpoint
+            .response
+            .as_ref()
+            .map_or("void".to_string(), typescript_type);
+
+        let mut path_params = endpoint.path_params.iter();
+        let path: String = route_segments(&endpoint.route)
+            .iter()
+            .map(|segment| match segment {
+                Some(literal) => format!("/{}", literal),
+                None => format!(
+                    "/${{encodeURIComponent(String({}))}}",
+                    path_params
+                        .next()
+                        .map_or("undefined".to_string(), |param| { typescript_ident(param) })
+                ),
+            })
+            .collect();
+
+        let body = match endpoint.request {
+            Some(_) => ", body",
+            None => "",
+        };
+
+        code.push_str(&format!(
+            "\n  async {}({}): Promise<{}> {{\n    return this.request<{}>(\"{}\", `{}`{});\n  }}\n",
+            typescript_ident(&endpoint.name),
+            params.join(", "),
+            return_type,
+            return_type,
+            endpoint.method.as_str().to_uppercase(),
+            path,
+            body
+        ));
+    }
+
+    code.push_str("}\n");
+    code
+}
+
+// Writes the TypeScript fetch client and the Rust reqwest client crate into clients/
+pub fn save_api_clients(factsheet: &FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+    let routes = factsheet
+        .api_endpoint_schema
+        .as_ref()
+        .ok_or("No api endpoints to generate clients for")?;
+    let entities = factsheet
+        .data_model
+        .as_ref()
+        .map_or(&[][..], |data_model| &data_model.entities[..]);
+
+    let model = ClientModel::new(routes, entities);
+
+    save_web_server_file(
+        &format!("{}/Cargo.toml", RUST_CLIENT_PATH),
+        RUST_CLIENT_MANIFEST,
+    )?;
+    save_web_server_file(
+        &format!("{}/src/lib.rs", RUST_CLIENT_PATH),
+        &render_rust_client(&model),
+    )?;
+    save_web_server_file(
+        &format!(
+            "{}/examples/{}.rs",
+            RUST_CLIENT_PATH, RUST_CLIENT_SMOKE_EXAMPLE
+        ),
+        &render_rust_smoke_example(&model),
+    )?;
+    save_web_server_file(
+        &format!("{}/client.ts", TYPESCRIPT_CLIENT_PATH),
+        &render_typescript_client(&model),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample_model() -> ClientModel {
+        let entities: Vec<Entity> = serde_json::from_value(json!([{
+            "name": "Task",
+            "fields": [
+                { "name": "id", "field_type": "integer" },
+                { "name": "type", "field_type": "string" },
+                { "name": "dueDate", "field_type": "date_time", "is_optional": true }
+            ]
+        }]))
+        .expect("Failed to decode entities");
+
+        let task = TypeSchema::Object(BTreeMap::from([
+            ("id".to_string(), TypeSchema::Number),
+            ("type".to_string(), TypeSchema::String),
+            ("dueDate".to_string(), TypeSchema::String),
+        ]));
+        let login = TypeSchema::Object(BTreeMap::from([
+            ("username".to_string(), TypeSchema::String),
+            (
+                "tags".to_string(),
+                TypeSchema::Array(Box::new(TypeSchema::Unknown)),
+            ),
+        ]));
+
+        let routes = vec![
+            RouteObject::new(HttpMethod::Post, "/task", Some(task.clone()), None),
+            RouteObject::new(
+                HttpMethod::Get,
+                "/task",
+                None,
+                Some(TypeSchema::Array(Box::new(task))),
+            ),
+            RouteObject::new(HttpMethod::Delete, "/user/{user_id}/task/{id}", None, None),
+            RouteObject::new(HttpMethod::Options, "/task", None, None),
+            RouteObject::new(
+                HttpMethod::Post,
+                "/login",
+                Some(login),
+                Some(TypeSchema::Bool),
+            ),
+        ];
+
+        ClientModel::new(&routes, &entities)
+    }
+
+    #[test]
+    fn tests_render_rust_client() {
+        let model = sample_model();
+
+        let client = render_rust_client(&model);
+        syn::parse_file(&client).expect("Generated client is not valid Rust");
+
+        assert!(client.contains("    pub r#type: String,\n"));
+        assert!(client.contains("    #[serde(rename = \"dueDate\")]\n"));
+        assert!(client.contains("    pub due_date: Option<String>,\n"));
+        assert!(client.contains("pub struct PostLoginRequest {"));
+        assert!(client.contains("pub tags: Vec<serde_json::Value>,"));
+        assert!(client.contains(
+            "pub async fn delete_user_user_id_task_id(&self, user_id: impl std::fmt::Display, id: impl std::fmt::Display) -> Result<(), reqwest::Error>"
+        ));
+        assert!(client
+            .contains("let url = format!(\"{}/user/{}/task/{}\", self.base_url, user_id, id);"));
+        assert!(
+            client.contains("pub async fn get_task(&self) -> Result<Vec<Task>, reqwest::Error>")
+        );
+        assert!(client.contains(
+            "self.http.request(reqwest::Method::OPTIONS, url).send().await?.error_for_status()?"
+        ));
+
+        let smoke = render_rust_smoke_example(&model);
+        syn::parse_file(&smoke).expect("Generated smoke example is not valid Rust");
+        assert!(smoke.contains("match client.get_task().await {"));
+        assert!(smoke.contains("Err(error) if is_auth_required(&error) =>"));
+        assert!(!smoke.contains("delete_user"));
+    }
+
+    #[test]
+    fn tests_render_typescript_client() {
+        let client = render_typescript_client(&sample_model());
+
+        assert!(client.contains(
+            "export interface Task {\n  id: number;\n  type: string;\n  dueDate?: string;\n}"
+        ));
+        assert!(client.contains("async postTask(body: Task): Promise<void> {"));
+        assert!(client.contains(
+            "return this.request<void>(\"DELETE\", `/user/${encodeURIComponent(String(userId))}/task/${encodeURIComponent(String(id))}`);"
+        ));
+        assert!(client.contains("async postLogin(body: PostLoginRequest): Promise<boolean> {"));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use serde_json::{json, Value};
+
+use crate::{
+    helpers::web_server::{save_web_server_file, WEB_SERVER_URL},
+    models::agents::agent_traits::{FactSheet, RouteObject},
+};
+
+use super::operation_name;
+
+const COLLECTIONS_PATH: &str = "collections";
+const POSTMAN_SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+const INSOMNIA_WORKSPACE_ID: &str = "wrk_rustptty";
+const INSOMNIA_ENVIRONMENT_ID: &str = "env_rustptty";
+const EXAMPLE_PATH_PARAM: &str = "1";
+
+fn request_name(route: &RouteObject) -> String {
+    format!("{} {}", route.method.as_str().to_uppercase(), route.route)
+}
+
+fn example_body(route: &RouteObject) -> Option<String> {
+    route.request_body.as_ref().map(|body| {
+        serde_json::to_string_pretty(&body.example_value()).expect("Failed to serialize example")
+    })
+}
+
+// Rewrites each {param} segment with the given template, such as :id or {{id}}
+fn templated_route(route: &RouteObject, template: impl Fn(&str) -> String) -> String {
+    let mut params = route.path_params.iter();
+
+    route
+        .route
+        .split('/')
+        .map(|segment| match segment.starts_with('{') {
+            true => params
+                .next()
+                .map_or(segment.to_string(), |param| template(param)),
+            false => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn path_param_names(routes: &[RouteObject]) -> BTreeSet<&str> {
+    routes
+        .iter()
+        .flat_map(|route| route.path_params.iter().map(String::as_str))
+        .collect()
+}
+
+// Postman v2.1 collection with a baseUrl collection variable and :param path variables
+pub fn build_postman_collection(title: &str, routes: &[RouteObject]) -> Value {
+    let items: Vec<Value> = routes
+        .iter()
+        .map(|route| {
+            let path = templated_route(route, |param| format!(":{}", param));
+            let variables: Vec<Value> = route
+                .path_params
+                .iter()
+                .map(|param| json!({ "key": param, "value": EXAMPLE_PATH_PARAM }))
+                .collect();
+
+            let mut request = json!({
+                "method": route.method.as_str().to_uppercase(),
+                "header": [],
+                "url": {
+                    "raw": format!("{{{{baseUrl}}}}{}", path),
+                    "host": ["{{baseUrl}}"],
+                    "path": path.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>(),
+                    "variable": variables,
+                },
+            });
+
+            if let Some(body) = example_body(route) {
+                request["header"] = json!([{ "key": "Content-Type", "value": "application/json" }]);
+                request["body"] = json!({
+                    "mode": "raw",
+                    "raw": body,
+                    "options": { "raw": { "language": "json" } },
+                });
+            }
+
+            json!({ "name": request_name(route), "request": request })
+        })
+        .collect();
+
+    json!({
+        "info": { "name": title, "schema": POSTMAN_SCHEMA },
+        "variable": [{ "key": "baseUrl", "value": WEB_SERVER_URL }],
+        "item": items,
+    })
+}
+
+// Insomnia v4 export: one workspace, a base environment holding base_url and the path
+// parameters, and a request per route
+pub fn build_insomnia_export(title: &str, routes: &[RouteObject]) -> Value {
+    let mut environment = json!({ "base_url": WEB_SERVER_URL });
+    for param in path_param_names(routes) {
+        environment[param] = json!(EXAMPLE_PATH_PARAM);
+    }
+
+    let mut resources = vec![
+        json!({ "_id": INSOMNIA_WORKSPACE_ID, "_type": "workspace", "name": title }),
+        json!({
+            "_id": INSOMNIA_ENVIRONMENT_ID,
+            "_type": "environment",
+            "parentId": INSOMNIA_WORKSPACE_ID,
+            "name": "Base Environment",
+            "data": environment,
+        }),
+    ];
+
+    for route in routes {
+        let path = templated_route(route, |param| format!("{{{{ _.{} }}}}", param));
+
+        let (headers, body) = match example_body(route) {
+            Some(text) => (
+                json!([{ "name": "Content-Type", "value": "application/json" }]),
+                json!({ "mimeType": "application/json", "text": text }),
+            ),
+            None => (json!([]), json!({})),
+        };
+
+        resources.push(json!({
+            "_id": format!("req_{}", operation_name(route)),
+            "_type": "request",
+            "parentId": INSOMNIA_WORKSPACE_ID,
+            "name": request_name(route),
+            "method": route.method.as_str().to_uppercase(),
+            "url": format!("{{{{ _.base_url }}}}{}", path),
+            "headers": headers,
+            "body": body,
+        }));
+    }
+
+    json!({
+        "_type": "export",
+        "__export_format": 4,
+        "__export_source": "rustptty",
+        "resources": resources,
+    })
+}
+
+// .http file understood by the VS Code REST Client and JetBrains HTTP client
+pub fn build_http_file(routes: &[RouteObject]) -> String {
+    let mut http = format!("@baseUrl = {}\n", WEB_SERVER_URL);
+    for param in path_param_names(routes) {
+        http.push_str(&format!("@{} = {}\n", param, EXAMPLE_PATH_PARAM));
+    }
+
+    for route in routes {
+        let path = templated_route(route, |param| format!("{{{{{}}}}}", param));
+
+        http.push_str(&format!(
+            "\n### {}\n{} {{{{baseUrl}}}}{}\n",
+            request_name(route),
+            route.method.as_str().to_uppercase(),
+            path
+        ));
+
+        if let Some(body) = example_body(route) {
+            http.push_str(&format!("Content-Type: application/json\n\n{}\n", body));
+        }
+    }
+
+    http
+}
+
+// Writes the Postman, Insomnia and .http collections next to the generated backend
+pub fn save_request_collections(factsheet: &FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+    let routes = factsheet
+        .api_endpoint_schema
+        .as_ref()
+        .ok_or("No api endpoints to export")?;
+    let title = &factsheet.project_description;
+
+    save_web_server_file(
+        &format!("{}/postman_collection.json", COLLECTIONS_PATH),
+        &serde_json::to_string_pretty(&build_postman_collection(title, routes))?,
+    )?;
+    save_web_server_file(
+        &format!("{}/insomnia.json", COLLECTIONS_PATH),
+        &serde_json::to_string_pretty(&build_insomnia_export(title, routes))?,
+    )?;
+    save_web_server_file(
+        &format!("{}/api.http", COLLECTIONS_PATH),
+        &build_http_file(routes),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::models::general::schema::{HttpMethod, TypeSchema};
+
+    fn sample_routes() -> Vec<RouteObject> {
+        let task = TypeSchema::Object(BTreeMap::from([
+            ("id".to_string(), TypeSchema::Number),
+            ("name".to_string(), TypeSchema::String),
+        ]));
+
+        vec![
+            RouteObject::new(HttpMethod::Post, "/task", Some(task), None),
+            RouteObject::new(HttpMethod::Delete, "/task/{id}", None, None),
+        ]
+    }
+
+    #[test]
+    fn tests_build_postman_and_insomnia() {
+        let postman = build_postman_collection("Tasks", &sample_routes());
+        assert_eq!(postman["variable"][0]["key"], "baseUrl");
+        assert_eq!(postman["item"][0]["name"], "POST /task");
+        assert_eq!(
+            serde_json::from_str::<Value>(
+                postman["item"][0]["request"]["body"]["raw"]
+                    .as_str()
+                    .unwrap()
+            )
+            .unwrap(),
+            json!({ "id": 0, "name": "string" })
+        );
+        assert_eq!(
+            postman["item"][1]["request"]["url"]["raw"],
+            "{{baseUrl}}/task/:id"
+        );
+        assert_eq!(
+            postman["item"][1]["request"]["url"]["path"],
+            json!(["task", ":id"])
+        );
+
+        let insomnia = build_insomnia_export("Tasks", &sample_routes());
+        let resources = insomnia["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 4);
+        assert_eq!(resources[1]["data"]["id"], "1");
+        assert_eq!(resources[3]["url"], "{{ _.base_url }}/task/{{ _.id }}");
+        assert_eq!(resources[3]["_id"], "req_delete_task_id");
+    }
+
+    #[test]
+    fn tests_build_http_file() {
+        let http = build_http_file(&sample_routes());
+
+        assert!(http.starts_with("@baseUrl = http://127.0.0.1:8080\n@id = 1\n"));
+        assert!(http.contains(
+            "### POST /task\nPOST {{baseUrl}}/task\nContent-Type: application/json\n\n{\n  \"id\": 0,\n  \"name\": \"string\"\n}\n"
+        ));
+        assert!(http.ends_with("### DELETE /task/{id}\nDELETE {{baseUrl}}/task/{{id}}\n"));
+    }
+}
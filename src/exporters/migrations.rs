@@ -0,0 +1,354 @@
+use crate::{
+    helpers::web_server::save_web_server_file,
+    models::{
+        agents::agent_traits::{DataModel, Entity, FactSheet, FieldType, RelationKind},
+        general::persistence::SqlDialect,
+    },
+};
+
+// sqlx::migrate!() reads migrations/<version>_<description>.sql from the crate root
+pub const MIGRATION_PATH: &str = "migrations/0001_create_tables.sql";
+
+// CREATE TABLE statements for every entity, with foreign keys for many to one and one to one
+// relations and a join table for many to many. Referenced tables come first
+pub fn build_migration(data_model: &DataModel, dialect: SqlDialect) -> String {
+    let mut statements = vec![];
+
+    for entity in tables_in_dependency_order(&data_model.entities) {
+        statements.push(create_table(entity, dialect));
+
+        // A one to one foreign key is already indexed by its UNIQUE constraint
+        for relation in &entity.relations {
+            if relation.kind == RelationKind::ManyToOne {
+                let table = table_name(&entity.name);
+                statements.push(format!(
+                    "CREATE INDEX IF NOT EXISTS \"idx_{}_{}\" ON \"{}\" (\"{}\");",
+                    table, relation.via_field, table, relation.via_field
+                ));
+            }
+        }
+    }
+
+    for (entity, target) in many_to_many_pairs(data_model) {
+        statements.push(create_join_table(entity, target, dialect));
+    }
+
+    statements.join("\n\n") + "\n"
+}
+
+fn create_table(entity: &Entity, dialect: SqlDialect) -> String {
+    let mut lines: Vec<String> = entity
+        .fields
+        .iter()
+        .map(|field| {
+            let column_type = column_type(field.field_type, dialect);
+
+            if field.name == "id" {
+                format!("    \"id\" {} PRIMARY KEY", column_type)
+            } else if field.is_optional {
+                format!("    \"{}\" {}", field.name, column_type)
+            } else {
+                format!("    \"{}\" {} NOT NULL", field.name, column_type)
+            }
+        })
+        .collect();
+
+    for relation in entity.relations.iter().filter(|r| is_foreign_key(r.kind)) {
+        if relation.kind == RelationKind::OneToOne {
+            lines.push(format!("    UNIQUE (\"{}\")", relation.via_field));
+        }
+
+        lines.push(format!(
+            "    FOREIGN KEY (\"{}\") REFERENCES \"{}\" (\"id\")",
+            relation.via_field,
+            table_name(&relation.target)
+        ));
+    }
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS \"{}\" (\n{}\n);",
+        table_name(&entity.name),
+        lines.join(",\n")
+    )
+}
+
+fn create_join_table(entity: &Entity, target: &Entity, dialect: SqlDialect) -> String {
+    let entity_table = table_name(&entity.name);
+    let target_table = table_name(&target.name);
+
+    format!(
+        "CREATE TABLE IF NOT EXISTS \"{0}_{1}\" (\n    \"{0}_id\" {2} NOT NULL,\n    \"{1}_id\" {3} NOT NULL,\n    PRIMARY KEY (\"{0}_id\", \"{1}_id\"),\n    FOREIGN KEY (\"{0}_id\") REFERENCES \"{0}\" (\"id\"),\n    FOREIGN KEY (\"{1}_id\") REFERENCES \"{1}\" (\"id\")\n);",
+        entity_table,
+        target_table,
+        column_type(id_type(entity), dialect),
+        column_type(id_type(target), dialect),
+    )
+}
+
+fn is_foreign_key(kind: RelationKind) -> bool {
+    matches!(kind, RelationKind::ManyToOne | RelationKind::OneToOne)
+}
+
+fn column_type(field_type: FieldType, dialect: SqlDialect) -> &'static str {
+    match (field_type, dialect) {
+        (FieldType::String, _) => "TEXT",
+        (FieldType::Integer, SqlDialect::Sqlite) => "INTEGER",
+        (FieldType::Integer, SqlDialect::Postgres) => "BIGINT",
+        (FieldType::Float, SqlDialect::Sqlite) => "REAL",
+        (FieldType::Float, SqlDialect::Postgres) => "DOUBLE PRECISION",
+        (FieldType::Boolean, _) => "BOOLEAN",
+        (FieldType::DateTime, SqlDialect::Sqlite) => "TEXT",
+        (FieldType::DateTime, SqlDialect::Postgres) => "TIMESTAMPTZ",
+        (FieldType::Uuid, SqlDialect::Sqlite) => "TEXT",
+        (FieldType::Uuid, SqlDialect::Postgres) => "UUID",
+    }
+}
+
+fn id_type(entity: &Entity) -> FieldType {
+    entity
+        .fields
+        .iter()
+        .find(|field| field.name == "id")
+        .map_or(FieldType::Integer, |field| field.field_type)
+}
+
+// TaskItem becomes task_item
+fn table_name(entity_name: &str) -> String {
+    let mut name = String::new();
+
+    for (i, c) in entity_name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+
+    name
+}
+
+// Postgres needs a referenced table to exist first. Cycles keep the data model order
+fn tables_in_dependency_order(entities: &[Entity]) -> Vec<&Entity> {
+    let mut ordered: Vec<&Entity> = vec![];
+    let mut remaining: Vec<&Entity> = entities.iter().collect();
+
+    while !remaining.is_empty() {
+        let ready = remaining.iter().position(|entity| {
+            entity.relations.iter().all(|relation| {
+                !is_foreign_key(relation.kind)
+                    || relation.target == entity.name
+                    || ordered.iter().any(|done| done.name == relation.target)
+                    || !entities.iter().any(|other| other.name == relation.target)
+            })
+        });
+
+        ordered.push(remaining.remove(ready.unwrap_or(0)));
+    }
+
+    ordered
+}
+
+// Each many to many relation once, even when both sides declare it
+fn many_to_many_pairs(data_model: &DataModel) -> Vec<(&Entity, &Entity)> {
+    let mut pairs: Vec<(&Entity, &Entity)> = vec![];
+
+    for entity in &data_model.entities {
+        for relation in &entity.relations {
+            if relation.kind != RelationKind::ManyToMany {
+                continue;
+            }
+
+            let Some(target) = data_model
+                .entities
+                .iter()
+                .find(|other| other.name == relation.target)
+            else {
+                continue;
+            };
+
+            let declared = pairs.iter().any(|(a, b)| {
+                (a.name == entity.name && b.name == target.name)
+                    || (a.name == target.name && b.name == entity.name)
+            });
+            if !declared {
+                pairs.push((entity, target));
+            }
+        }
+    }
+
+    pairs
+}
+
+// Writes the migration the generated server runs on startup and returns its SQL
+pub fn save_migrations(factsheet: &FactSheet) -> Result<Option<String>, std::io::Error> {
+    let (Some(dialect), Some(data_model)) = (
+        factsheet.persistence.sql_dialect(),
+        factsheet.data_model.as_ref(),
+    ) else {
+        return Ok(None);
+    };
+
+    let migration = build_migration(data_model, dialect);
+    save_web_server_file(MIGRATION_PATH, &migration)?;
+
+    Ok(Some(migration))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+
+    use rusqlite::Connection;
+    use serde_json::json;
+    use sqlparser::{dialect::PostgreSqlDialect, parser::Parser};
+
+    use super::*;
+
+    // Task is declared before the User it references, Profile is one to one with User
+    fn blog_data_model() -> DataModel {
+        serde_json::from_value(json!({
+            "entities": [
+                {
+                    "name": "Task",
+                    "fields": [
+                        { "name": "id", "field_type": "integer" },
+                        { "name": "name", "field_type": "string" },
+                        { "name": "estimate", "field_type": "float", "is_optional": true },
+                        { "name": "created_at", "field_type": "date_time" },
+                        { "name": "user_id", "field_type": "integer" }
+                    ],
+                    "relations": [
+                        { "kind": "many_to_one", "target": "User", "via_field": "user_id" },
+                        { "kind": "many_to_many", "target": "Tag", "via_field": "id" }
+                    ]
+                },
+                {
+                    "name": "User",
+                    "fields": [
+                        { "name": "id", "field_type": "integer" },
+                        { "name": "username", "field_type": "string" }
+                    ],
+                    "relations": [{ "kind": "one_to_many", "target": "Task", "via_field": "id" }]
+                },
+                {
+                    "name": "UserProfile",
+                    "fields": [
+                        { "name": "id", "field_type": "uuid" },
+                        { "name": "user_id", "field_type": "integer" }
+                    ],
+                    "relations": [{ "kind": "one_to_one", "target": "User", "via_field": "user_id" }]
+                },
+                {
+                    "name": "Tag",
+                    "fields": [
+                        { "name": "id", "field_type": "integer" },
+                        { "name": "label", "field_type": "string" }
+                    ],
+                    "relations": [{ "kind": "many_to_many", "target": "Task", "via_field": "id" }]
+                }
+            ]
+        }))
+        .expect("Failed to decode data model")
+    }
+
+    const VALID_ROWS: &str = r#"
+        INSERT INTO "user" ("id", "username") VALUES (1, 'ada');
+        INSERT INTO "task" ("id", "name", "created_at", "user_id") VALUES (1, 'write', '2024-01-01T00:00:00Z', 1);
+        INSERT INTO "tag" ("id", "label") VALUES (1, 'home');
+        INSERT INTO "task_tag" ("task_id", "tag_id") VALUES (1, 1);
+        INSERT INTO "user_profile" ("id", "user_id") VALUES ('6f9619ff-8b86-d011-b42d-00cf4fc964ff', 1);
+    "#;
+    const UNKNOWN_USER_ROW: &str = r#"
+        INSERT INTO "task" ("id", "name", "created_at", "user_id") VALUES (2, 'read', '2024-01-01T00:00:00Z', 42);
+    "#;
+
+    #[test]
+    fn tests_build_migration_runs_on_sqlite() {
+        let migration = build_migration(&blog_data_model(), SqlDialect::Sqlite);
+        assert!(migration.contains("\"estimate\" REAL,\n"));
+        assert!(migration.contains("UNIQUE (\"user_id\")"));
+
+        let conn = Connection::open_in_memory().expect("Failed to open sqlite");
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .expect("Failed to enable foreign keys");
+        conn.execute_batch(&migration)
+            .expect("Failed to run migration");
+        conn.execute_batch(&migration)
+            .expect("Failed to run migration twice");
+
+        conn.execute_batch(VALID_ROWS)
+            .expect("Failed to insert valid rows");
+        assert!(conn.execute_batch(UNKNOWN_USER_ROW).is_err());
+        assert!(conn
+            .execute_batch(
+                "INSERT INTO \"user_profile\" (\"id\", \"user_id\") VALUES ('other', 1);"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn tests_build_migration_postgres_dialect() {
+        let migration = build_migration(&blog_data_model(), SqlDialect::Postgres);
+
+        let statements =
+            Parser::parse_sql(&PostgreSqlDialect {}, &migration).expect("Invalid Postgres SQL");
+        // user, task and its index, user_profile, tag, then the task_tag join table
+        assert_eq!(statements.len(), 6);
+
+        let position = |table: &str| {
+            migration
+                .find(&format!("CREATE TABLE IF NOT EXISTS \"{}\"", table))
+                .unwrap_or_else(|| panic!("No table {}", table))
+        };
+        assert!(position("user") < position("task"));
+        assert!(position("user") < position("user_profile"));
+        assert!(position("tag") < position("task_tag"));
+
+        assert!(migration.contains("\"id\" BIGINT PRIMARY KEY"));
+        assert!(migration.contains("\"id\" UUID PRIMARY KEY"));
+        assert!(migration.contains("\"created_at\" TIMESTAMPTZ NOT NULL"));
+        assert!(migration.contains("\"estimate\" DOUBLE PRECISION,\n"));
+    }
+
+    fn run_psql(url: &str, script: &str) -> bool {
+        let mut psql = Command::new("psql")
+            .args([url, "-q", "-v", "ON_ERROR_STOP=1"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to run psql");
+
+        psql.stdin
+            .take()
+            .expect("No psql stdin")
+            .write_all(script.as_bytes())
+            .expect("Failed to write to psql");
+
+        psql.wait().expect("psql did not finish").success()
+    }
+
+    #[test]
+    #[ignore = "needs psql and a Postgres server in POSTGRES_TEST_URL, such as a local postgres container"]
+    fn tests_build_migration_runs_on_postgres() {
+        let url = std::env::var("POSTGRES_TEST_URL").expect("POSTGRES_TEST_URL is not set");
+        let migration = build_migration(&blog_data_model(), SqlDialect::Postgres);
+        let schema = format!("rustptty_migration_{}", std::process::id());
+        let in_schema = |sql: &str| format!("SET search_path TO \"{}\";\n{}", schema, sql);
+
+        assert!(run_psql(&url, &format!("CREATE SCHEMA \"{}\";", schema)));
+
+        let applied = run_psql(&url, &in_schema(&format!("{0}\n{0}", migration)))
+            && run_psql(&url, &in_schema(VALID_ROWS));
+        let unknown_user_rejected = !run_psql(&url, &in_schema(UNKNOWN_USER_ROW));
+
+        assert!(run_psql(
+            &url,
+            &format!("DROP SCHEMA \"{}\" CASCADE;", schema)
+        ));
+        assert!(applied);
+        assert!(unknown_user_rejected);
+    }
+}
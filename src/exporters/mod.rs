@@ -0,0 +1,34 @@
+pub mod clients;
+pub mod collections;
+pub mod migrations;
+pub mod openapi;
+
+use std::collections::BTreeMap;
+
+use crate::models::{
+    agents::agent_traits::{Entity, RouteObject},
+    general::schema::TypeSchema,
+};
+
+// get /task/{id} becomes get_task_id
+pub fn operation_name(route: &RouteObject) -> String {
+    format!("{}{}", route.method, route.route)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+// The entity whose fields are exactly the keys of a request body or response object
+pub fn find_matching_entity<'a>(
+    fields: &BTreeMap<String, TypeSchema>,
+    entities: &'a [Entity],
+) -> Option<&'a Entity> {
+    entities.iter().find(|entity| {
+        entity.fields.len() == fields.len()
+            && entity
+                .fields
+                .iter()
+                .all(|field| fields.contains_key(&field.name))
+    })
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    helpers::web_server::{save_web_server_file, WEB_SERVER_URL},
+    models::{
+        agents::agent_traits::{DataModel, Entity, FactSheet, FieldType, RouteObject},
+        general::schema::TypeSchema,
+    },
+};
+
+use super::{find_matching_entity, operation_name};
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+// Builds an OpenAPI 3.1 document from the extracted endpoints. Bodies that match a data model
+// entity field for field are emitted as a $ref to that entity's component schema
+pub fn build_openapi_spec(
+    title: &str,
+    routes: &[RouteObject],
+    data_model: Option<&DataModel>,
+) -> Value {
+    let entities = data_model.map_or(&[][..], |data_model| &data_model.entities[..]);
+
+    let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for route in routes {
+        paths
+            .entry(route.route.clone())
+            .or_default()
+            .insert(route.method.to_string(), operation(route, entities));
+    }
+
+    let mut spec = json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": title, "version": "1.0.0" },
+        "servers": [{ "url": WEB_SERVER_URL }],
+        "paths": paths,
+    });
+
+    if !entities.is_empty() {
+        let schemas: Map<String, Value> = entities
+            .iter()
+            .map(|entity| (entity.name.clone(), entity_schema(entity)))
+            .collect();
+        spec["components"] = json!({ "schemas": schemas });
+    }
+
+    spec
+}
+
+fn operation(route: &RouteObject, entities: &[Entity]) -> Value {
+    let mut operation = json!({ "operationId": operation_name(route) });
+
+    if !route.path_params.is_empty() {
+        let parameters: Vec<Value> = route
+            .path_params
+            .iter()
+            .map(|param| {
+                json!({
+                    "name": param,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        operation["parameters"] = json!(parameters);
+    }
+
+    if let Some(body) = &route.request_body {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": body_schema(body, entities) } },
+        });
+    }
+
+    operation["responses"] = match &route.response {
+        Some(response) => json!({
+            "200": {
+                "description": "Successful response",
+                "content": { "application/json": { "schema": body_schema(response, entities) } },
+            }
+        }),
+        None => json!({ "200": { "description": "Successful response" } }),
+    };
+
+    operation
+}
+
+fn body_schema(schema: &TypeSchema, entities: &[Entity]) -> Value {
+    match schema {
+        TypeSchema::String => json!({ "type": "string" }),
+        TypeSchema::Number => json!({ "type": "number" }),
+        TypeSchema::Bool => json!({ "type": "boolean" }),
+        TypeSchema::Unknown => json!({}),
+        TypeSchema::Array(item) => json!({ "type": "array", "items": body_schema(item, entities) }),
+        TypeSchema::Object(fields) => {
+            if let Some(entity) = find_matching_entity(fields, entities) {
+                return json!({ "$ref": format!("#/components/schemas/{}", entity.name) });
+            }
+
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(name, field)| (name.clone(), body_schema(field, entities)))
+                .collect();
+
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": fields.keys().collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+fn entity_schema(entity: &Entity) -> Value {
+    let properties: Map<String, Value> = entity
+        .fields
+        .iter()
+        .map(|field| (field.name.clone(), field_type_schema(field.field_type)))
+        .collect();
+
+    let required: Vec<&String> = entity
+        .fields
+        .iter()
+        .filter(|field| !field.is_optional)
+        .map(|field| &field.name)
+        .collect();
+
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn field_type_schema(field_type: FieldType) -> Value {
+    match field_type {
+        FieldType::String => json!({ "type": "string" }),
+        FieldType::Integer => json!({ "type": "integer" }),
+        FieldType::Float => json!({ "type": "number" }),
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::DateTime => json!({ "type": "string", "format": "date-time" }),
+        FieldType::Uuid => json!({ "type": "string", "format": "uuid" }),
+    }
+}
+
+// Writes openapi.json and openapi.yaml next to the generated backend
+pub fn save_openapi_spec(factsheet: &FactSheet) -> Result<(), Box<dyn std::error::Error>> {
+    let routes = factsheet
+        .api_endpoint_schema
+        .as_ref()
+        .ok_or("No api endpoints to export")?;
+
+    let spec = build_openapi_spec(
+        &factsheet.project_description,
+        routes,
+        factsheet.data_model.as_ref(),
+    );
+
+    save_web_server_file("openapi.json", &serde_json::to_string_pretty(&spec)?)?;
+    save_web_server_file("openapi.yaml", &serde_yaml::to_string(&spec)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+
+    use super::*;
+    use crate::models::general::schema::HttpMethod;
+
+    fn task_data_model() -> DataModel {
+        serde_json::from_value(json!({
+            "entities": [{
+                "name": "Task",
+                "fields": [
+                    { "name": "id", "field_type": "integer" },
+                    { "name": "name", "field_type": "string" },
+                    { "name": "due", "field_type": "date_time", "is_optional": true }
+                ]
+            }]
+        }))
+        .expect("Failed to decode data model")
+    }
+
+    #[test]
+    fn tests_openapi_spec_round_trip() {
+        let task = TypeSchema::Object(BTreeMap::from([
+            ("id".to_string(), TypeSchema::Number),
+            ("name".to_string(), TypeSchema::String),
+            ("due".to_string(), TypeSchema::String),
+        ]));
+        let routes = vec![
+            RouteObject::new(HttpMethod::Post, "/task", Some(task.clone()), None),
+            RouteObject::new(
+                HttpMethod::Get,
+                "/task/{id}",
+                None,
+                Some(TypeSchema::Array(Box::new(task))),
+            ),
+            RouteObject::new(
+                HttpMethod::Delete,
+                "/task/{id}",
+                None,
+                Some(TypeSchema::Object(BTreeMap::from([(
+                    "deleted".to_string(),
+                    TypeSchema::Bool,
+                )]))),
+            ),
+        ];
+
+        let spec = build_openapi_spec("Task tracker", &routes, Some(&task_data_model()));
+
+        let parsed = oas3::from_json(serde_json::to_string(&spec).unwrap())
+            .expect("Generated spec is not valid OpenAPI");
+        parsed.validate_version().expect("Spec is not OpenAPI 3.1");
+
+        let get_task = parsed
+            .operation(&Method::GET, "/task/{id}")
+            .expect("Missing GET /task/{id}");
+        assert_eq!(get_task.operation_id.as_deref(), Some("get_task_id"));
+        assert_eq!(get_task.parameters.len(), 1);
+
+        let post_task = parsed
+            .operation(&Method::POST, "/task")
+            .expect("Missing POST /task");
+        let request_body = post_task
+            .request_body
+            .as_ref()
+            .expect("Missing request body")
+            .resolve(&parsed)
+            .expect("Request body does not resolve");
+        assert!(request_body.content.contains_key("application/json"));
+
+        assert_eq!(
+            spec["paths"]["/task"]["post"]["requestBody"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/Task" })
+        );
+        assert_eq!(
+            spec["components"]["schemas"]["Task"]["required"],
+            json!(["id", "name"])
+        );
+
+        let from_yaml = oas3::from_yaml(serde_yaml::to_string(&spec).unwrap())
+            .expect("Generated YAML spec is not valid OpenAPI");
+        assert_eq!(from_yaml.operations().count(), 3);
+    }
+}
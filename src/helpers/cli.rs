@@ -1,19 +1,49 @@
-use std::io::{stdin, stdout};
+use std::{
+    cell::RefCell,
+    future::Future,
+    io::{stdin, stdout},
+    path::PathBuf,
+};
 
 use crossterm::{
     style::{Color, ResetColor, SetForegroundColor},
     ExecutableCommand,
 };
 
-#[derive(PartialEq, Debug)]
+use crate::models::{
+    agents_manager::pipeline::DEFAULT_PIPELINE_FILE,
+    general::{edit_mode::EditMode, framework::WebFramework, persistence::PersistenceBackend},
+};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum PrintCommand {
     AICall,
     UnitTest,
     Issue,
 }
 
+// A message an agent printed while running alongside others
+pub type AgentMsg = (PrintCommand, String, String);
+
+tokio::task_local! {
+    static AGENT_MSGS: RefCell<Vec<AgentMsg>>;
+}
+
 impl PrintCommand {
     pub fn print_agent_msg(&self, agent_position: &str, agent_statement: &str) {
+        let is_buffered = AGENT_MSGS
+            .try_with(|msgs| {
+                msgs.borrow_mut().push((
+                    *self,
+                    agent_position.to_string(),
+                    agent_statement.to_string(),
+                ))
+            })
+            .is_ok();
+        if is_buffered {
+            return;
+        }
+
         let mut stdout = stdout();
 
         let statement_color = match self {
@@ -32,6 +62,23 @@ impl PrintCommand {
     }
 }
 
+// Holds back the messages printed while the future runs, so agents running at the same time
+// can have theirs printed one agent after another
+pub async fn buffer_agent_msgs<F: Future>(future: F) -> (F::Output, Vec<AgentMsg>) {
+    AGENT_MSGS
+        .scope(RefCell::new(vec![]), async {
+            let output = future.await;
+            (output, AGENT_MSGS.with(|msgs| msgs.take()))
+        })
+        .await
+}
+
+pub fn print_agent_msgs(msgs: &[AgentMsg]) {
+    for (command, agent_position, agent_statement) in msgs {
+        command.print_agent_msg(agent_position, agent_statement);
+    }
+}
+
 pub fn get_user_response(question: &str) -> String {
     let mut stdout = stdout();
 
@@ -49,9 +96,149 @@ pub fn get_user_response(question: &str) -> String {
     return user_response.trim().to_string();
 }
 
+#[derive(Debug, PartialEq)]
+pub enum CliCommand {
+    Run(RunOptions),
+    Rollback(usize),
+    Amend(Option<String>),
+    Import(PathBuf),
+    Tests,
+    Agents(Option<PathBuf>),
+    PipelineCheck(PathBuf),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RunOptions {
+    pub framework: WebFramework,
+    pub persistence: PersistenceBackend,
+    pub template_dirs: Vec<PathBuf>,
+    pub edit_mode: EditMode,
+    pub pipeline: Option<PathBuf>,
+}
+
+// `rollback <n>` restores iteration n of the generated backend, `amend [request]` changes it,
+// `import <path>` takes an existing crate as the backend and `tests` writes integration tests
+// for it, `agents [pipeline file]` lists the agents a run can use and `pipeline check [file]`
+// lints a pipeline file, pipeline.toml by default. Anything else starts a run
+pub fn parse_command(args: &[String]) -> Result<CliCommand, String> {
+    match args.first().map(String::as_str) {
+        Some("rollback") => {
+            let usage =
+                "Usage: rollback <n>, where n is the iteration number from git log (oldest is 1)";
+            let iteration = match args.get(1..) {
+                Some([iteration]) => iteration.parse::<usize>().map_err(|_| usage)?,
+                _ => return Err(usage.to_string()),
+            };
+            if iteration == 0 {
+                return Err(usage.to_string());
+            }
+            Ok(CliCommand::Rollback(iteration))
+        }
+        Some("amend") => {
+            let request = args[1..].join(" ");
+            Ok(CliCommand::Amend(
+                Some(request).filter(|request| !request.trim().is_empty()),
+            ))
+        }
+        Some("import") => {
+            let crate_path = match args.get(1..) {
+                Some([crate_path]) => PathBuf::from(crate_path),
+                _ => {
+                    return Err("Usage: import <path to an actix, axum or rocket crate>".to_string())
+                }
+            };
+            if !crate_path.join("Cargo.toml").is_file() {
+                return Err(format!("{} has no Cargo.toml", crate_path.display()));
+            }
+            Ok(CliCommand::Import(crate_path))
+        }
+        Some("tests") if args.len() == 1 => Ok(CliCommand::Tests),
+        Some("agents") => match args.get(1..) {
+            Some([]) => Ok(CliCommand::Agents(None)),
+            Some([config]) => Ok(CliCommand::Agents(Some(PathBuf::from(config)))),
+            _ => Err("Usage: agents [path to a pipeline file]".to_string()),
+        },
+        Some("pipeline") => match args.get(1..) {
+            Some([check]) if check == "check" => Ok(CliCommand::PipelineCheck(PathBuf::from(
+                DEFAULT_PIPELINE_FILE,
+            ))),
+            Some([check, path]) if check == "check" => {
+                Ok(CliCommand::PipelineCheck(PathBuf::from(path)))
+            }
+            _ => Err("Usage: pipeline check [path to a pipeline file]".to_string()),
+        },
+        _ => parse_run_options(args).map(CliCommand::Run),
+    }
+}
+
+// Reads run options such as --framework axum, --persistence sqlite, --template-dir ./templates
+// --edit-mode rewrite or --pipeline pipeline.toml from the command line arguments. --template-dir can be repeated, earlier directories win.
+// --agents agents.toml still works and takes the lineup as a one-stage pipeline
+pub fn parse_run_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+
+        match flag {
+            "--framework" => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or("--framework needs a value: actix, axum or rocket")?;
+                options.framework = value.parse()?;
+            }
+            "--persistence" => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or("--persistence needs a value: json, sqlite or postgres")?;
+                options.persistence = value.parse()?;
+            }
+            "--template-dir" => {
+                let dir = inline_value
+                    .or_else(|| args.next().cloned())
+                    .map(PathBuf::from)
+                    .ok_or("--template-dir needs a directory")?;
+                if !dir.is_dir() {
+                    return Err(format!(
+                        "Template directory {} does not exist",
+                        dir.display()
+                    ));
+                }
+                options.template_dirs.push(dir);
+            }
+            "--edit-mode" => {
+                let value = inline_value
+                    .or_else(|| args.next().cloned())
+                    .ok_or("--edit-mode needs a value: patch or rewrite")?;
+                options.edit_mode = value.parse()?;
+            }
+            "--pipeline" | "--agents" => {
+                let path = inline_value
+                    .or_else(|| args.next().cloned())
+                    .map(PathBuf::from)
+                    .ok_or("--pipeline needs the path to a pipeline file")?;
+                if !path.is_file() {
+                    return Err(format!("Pipeline file {} does not exist", path.display()));
+                }
+                options.pipeline = Some(path);
+            }
+            _ => return Err(format!("Unknown option {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::PrintCommand;
+    use super::{
+        buffer_agent_msgs, parse_command, parse_run_options, CliCommand, EditMode, PathBuf,
+        PersistenceBackend, PrintCommand, RunOptions, WebFramework,
+    };
 
     #[test]
     fn test_prints_agent_msg() {
@@ -60,4 +247,121 @@ mod tests {
             "This is a test message, processing something!",
         );
     }
+
+    #[tokio::test]
+    async fn tests_buffer_agent_msgs() {
+        let (output, msgs) = buffer_agent_msgs(async {
+            PrintCommand::AICall.print_agent_msg("Frontend developer", "Writing the page");
+            PrintCommand::Issue.print_agent_msg("Frontend developer", "Page failed to load");
+            7
+        })
+        .await;
+
+        assert_eq!(output, 7);
+        assert_eq!(
+            msgs,
+            vec![
+                (
+                    PrintCommand::AICall,
+                    "Frontend developer".to_string(),
+                    "Writing the page".to_string()
+                ),
+                (
+                    PrintCommand::Issue,
+                    "Frontend developer".to_string(),
+                    "Page failed to load".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_parse_command() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            parse_command(&args(&["rollback", "3"])),
+            Ok(CliCommand::Rollback(3))
+        );
+        assert_eq!(
+            parse_command(&args(&["--framework", "axum"])),
+            Ok(CliCommand::Run(RunOptions {
+                framework: WebFramework::Axum,
+                ..RunOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse_command(&args(&["amend", "add", "pagination", "to", "tasks"])),
+            Ok(CliCommand::Amend(Some(
+                "add pagination to tasks".to_string()
+            )))
+        );
+        assert_eq!(
+            parse_command(&args(&["amend"])),
+            Ok(CliCommand::Amend(None))
+        );
+        let crate_path = env!("CARGO_MANIFEST_DIR");
+        assert_eq!(
+            parse_command(&args(&["import", crate_path])),
+            Ok(CliCommand::Import(PathBuf::from(crate_path)))
+        );
+        assert!(parse_command(&args(&["import", "/no/such/crate"])).is_err());
+        assert!(parse_command(&args(&["import"])).is_err());
+        assert_eq!(parse_command(&args(&["tests"])), Ok(CliCommand::Tests));
+        assert_eq!(
+            parse_command(&args(&["agents", "pipeline.yaml"])),
+            Ok(CliCommand::Agents(Some(PathBuf::from("pipeline.yaml"))))
+        );
+        assert!(parse_command(&args(&["agents", "a.toml", "b.toml"])).is_err());
+        assert_eq!(
+            parse_command(&args(&["pipeline", "check"])),
+            Ok(CliCommand::PipelineCheck(PathBuf::from("pipeline.toml")))
+        );
+        assert!(parse_command(&args(&["pipeline", "run"])).is_err());
+        assert!(parse_command(&args(&["rollback"])).is_err());
+        assert!(parse_command(&args(&["rollback", "0"])).is_err());
+        assert!(parse_command(&args(&["rollback", "latest"])).is_err());
+        assert!(parse_command(&args(&["rollback", "1", "2"])).is_err());
+    }
+
+    #[test]
+    fn tests_parse_run_options() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(parse_run_options(&[]), Ok(RunOptions::default()));
+        assert_eq!(
+            parse_run_options(&args(&["--framework", "axum"])).map(|opts| opts.framework),
+            Ok(WebFramework::Axum)
+        );
+        assert_eq!(
+            parse_run_options(&args(&["--framework=rocket"])).map(|opts| opts.framework),
+            Ok(WebFramework::Rocket)
+        );
+        assert_eq!(
+            parse_run_options(&args(&["--persistence", "sqlite"])).map(|opts| opts.persistence),
+            Ok(PersistenceBackend::Sqlite)
+        );
+        assert_eq!(
+            parse_run_options(&args(&["--edit-mode=rewrite"])).map(|opts| opts.edit_mode),
+            Ok(EditMode::Rewrite)
+        );
+        assert!(parse_run_options(&args(&["--edit-mode", "merge"])).is_err());
+        assert!(parse_run_options(&args(&["--framework"])).is_err());
+        assert!(parse_run_options(&args(&["--framework", "warp"])).is_err());
+        assert!(parse_run_options(&args(&["--verbose"])).is_err());
+
+        let template_dir = env!("CARGO_MANIFEST_DIR");
+        assert_eq!(
+            parse_run_options(&args(&["--template-dir", template_dir]))
+                .map(|opts| opts.template_dirs),
+            Ok(vec![PathBuf::from(template_dir)])
+        );
+        assert!(parse_run_options(&args(&["--template-dir", "/no/such/templates"])).is_err());
+        assert!(parse_run_options(&args(&["--pipeline", "/no/such/pipeline.toml"])).is_err());
+        assert!(parse_run_options(&args(&["--agents", "/no/such/agents.toml"])).is_err());
+        assert_eq!(
+            parse_run_options(&args(&["--agents", "Cargo.toml"])).map(|opts| opts.pipeline),
+            Ok(Some(PathBuf::from("Cargo.toml")))
+        );
+    }
 }
@@ -1,19 +1,38 @@
 use std::{
-    fs::File,
+    collections::BTreeSet,
+    fs::{self, File},
     io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-
-use crate::{apis::call_request::call_gpt, models::general::llm::Message};
+use serde_json::Value;
+
+use crate::{
+    apis::call_request::{call_gpt, model_choice},
+    models::{
+        agents::agent_traits::RouteObject,
+        general::{
+            backend_files::BackendFiles,
+            llm::{Message, ModelChoice},
+            template::CodeTemplate,
+        },
+    },
+};
 
 use super::cli::PrintCommand;
 
-const CODE_TEMPLATE_PATH: &str =
-    "/Users/gbubemismith/Documents/Rust/rustptty/assets/code_template.rs";
-const EXEC_MAIN_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/assets/main.rs";
+const EXEC_PROJECT_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/web_template/";
 const SCHEMA_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/schemas/api_schema.json";
+const FRONTEND_PATH: &str =
+    "/Users/gbubemismith/Documents/Rust/rustptty/web_template/static/index.html";
+const ARTIFACTS_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/web_template/artifacts/";
+const ROUTE_OBJECT_SCHEMA: &str = include_str!("../../schemas/route_object.schema.json");
+const STAGING_DIR: &str = ".rustptty-staging";
+const BACKUP_DIR: &str = ".rustptty-backup";
+const RUN_LOG_FILE: &str = "run_log.jsonl";
 
 pub fn extend_ai_function(ai_func: fn(&str) -> &'static str, func_input: &str) -> Message {
     let ai_function_str = ai_func(func_input);
@@ -37,12 +56,18 @@ pub async fn perfom_ai_call(
 
     PrintCommand::AICall.print_agent_msg(agent_position, agent_operation);
 
-    let llm_response_res = call_gpt(vec![extended_msg.clone()]).await;
+    let choice = model_choice(agent_operation);
+    if let Err(err) = log_model_choice(agent_position, agent_operation, &choice) {
+        let issue = format!("Failed to write the run log: {}", err);
+        PrintCommand::Issue.print_agent_msg(agent_position, &issue);
+    }
+
+    let llm_response_res = call_gpt(vec![extended_msg.clone()], &choice).await;
 
     match llm_response_res {
         Ok(resp) => resp,
         // Retry
-        Err(_) => call_gpt(vec![extended_msg])
+        Err(_) => call_gpt(vec![extended_msg], &choice)
             .await
             .expect("Retry call to llm failed!"),
     }
@@ -70,31 +95,189 @@ pub async fn check_status_code(client: &Client, url: &str) -> Result<u16, reqwes
     Ok(response.status().as_u16())
 }
 
-pub fn read_code_template_content() -> Result<impl BufRead, std::io::Error> {
-    let file = File::open(CODE_TEMPLATE_PATH)?;
+pub fn read_code_template_content(template: &CodeTemplate) -> Result<impl BufRead, std::io::Error> {
+    let file = File::open(&template.code_path)?;
 
     Ok(io::BufReader::new(file))
 }
 
-pub fn read_exec_main_contents() -> Result<impl BufRead, std::io::Error> {
-    let file = File::open(EXEC_MAIN_PATH)?;
+// Cargo.toml the generated project needs for the framework's code template
+pub fn read_framework_manifest(template: &CodeTemplate) -> Result<String, std::io::Error> {
+    fs::read_to_string(&template.manifest_path)
+}
+
+pub fn save_backend_files(files: &BackendFiles) -> Result<(), std::io::Error> {
+    write_files_atomically(Path::new(EXEC_PROJECT_PATH), files)
+}
 
-    Ok(io::BufReader::new(file))
+// Writes every file to a staging folder first, then swaps each top level entry (Cargo.toml,
+// src/) into place. A failed swap puts back what was there, and files the map no longer has
+// disappear with the old src/
+pub fn write_files_atomically(root: &Path, files: &BackendFiles) -> Result<(), std::io::Error> {
+    let staging = root.join(STAGING_DIR);
+    let backup = root.join(BACKUP_DIR);
+    for dir in [&staging, &backup] {
+        if dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+    }
+
+    let mut entries = BTreeSet::new();
+    for (path, content) in files.iter() {
+        let staged = staging.join(path);
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&staged, content)?;
+
+        if let Some(entry) = path.split('/').next() {
+            entries.insert(entry);
+        }
+    }
+
+    fs::create_dir_all(&backup)?;
+    let mut swapped = vec![];
+    for entry in entries {
+        if let Err(err) = swap_entry(root, &staging, &backup, entry) {
+            for entry in swapped.into_iter().rev() {
+                restore_entry(root, &backup, entry)?;
+            }
+            return Err(err);
+        }
+        swapped.push(entry);
+    }
+
+    fs::remove_dir_all(&staging)?;
+    fs::remove_dir_all(&backup)?;
+
+    Ok(())
+}
+
+fn swap_entry(root: &Path, staging: &Path, backup: &Path, entry: &str) -> io::Result<()> {
+    let live = root.join(entry);
+    if live.exists() {
+        fs::rename(&live, backup.join(entry))?;
+    }
+
+    if let Err(err) = fs::rename(staging.join(entry), &live) {
+        restore_entry(root, backup, entry)?;
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+fn restore_entry(root: &Path, backup: &Path, entry: &str) -> io::Result<()> {
+    let live = root.join(entry);
+    if live.is_dir() {
+        fs::remove_dir_all(&live)?;
+    } else if live.exists() {
+        fs::remove_file(&live)?;
+    }
+
+    let previous = backup.join(entry);
+    if previous.exists() {
+        fs::rename(previous, live)?;
+    }
+
+    Ok(())
+}
+
+pub fn save_frontend_code(content: &str) -> Result<(), std::io::Error> {
+    if let Some(parent) = Path::new(FRONTEND_PATH).parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = File::create(FRONTEND_PATH)?;
+    file.write_all(content.as_bytes())?;
+    file.flush()?;
+
+    Ok(())
 }
 
-pub fn save_backend_code(content: &str) -> Result<(), std::io::Error> {
-    let mut file = File::create(EXEC_MAIN_PATH)?;
+// Reports produced along the way (reviews, audits, ...) kept next to the generated project
+pub fn save_artifact(file_name: &str, content: &str) -> Result<(), std::io::Error> {
+    fs::create_dir_all(ARTIFACTS_PATH)?;
+
+    let mut file = File::create(Path::new(ARTIFACTS_PATH).join(file_name))?;
     file.write_all(content.as_bytes())?;
     file.flush()?;
 
     Ok(())
 }
 
-pub fn save_api_endpoints(mut reader: impl BufRead) -> Result<(), std::io::Error> {
-    let file = File::create(SCHEMA_PATH)?;
-    let mut writer = BufWriter::new(file);
+// Appends the model every LLM call used to artifacts/run_log.jsonl, one JSON object a line
+pub fn log_model_choice(
+    agent_position: &str,
+    ai_function: &str,
+    choice: &ModelChoice,
+) -> Result<(), std::io::Error> {
+    fs::create_dir_all(ARTIFACTS_PATH)?;
+    append_run_log(
+        &Path::new(ARTIFACTS_PATH).join(RUN_LOG_FILE),
+        agent_position,
+        ai_function,
+        choice,
+    )
+}
+
+fn append_run_log(
+    path: &Path,
+    agent_position: &str,
+    ai_function: &str,
+    choice: &ModelChoice,
+) -> Result<(), std::io::Error> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let entry = serde_json::json!({
+        "timestamp": timestamp,
+        "agent": agent_position,
+        "ai_function": ai_function,
+        "provider": choice.provider,
+        "model": choice.model,
+        "temperature": choice.temperature,
+        "max_tokens": choice.max_tokens,
+    });
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", entry)
+}
+
+// Checks endpoints against schemas/route_object.schema.json, returning every violation found
+pub fn validate_api_endpoints(endpoints: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let schema: Value = serde_json::from_str(ROUTE_OBJECT_SCHEMA)?;
+    let validator = jsonschema::validator_for(&schema)?;
+
+    let errors: Vec<String> = validator
+        .iter_errors(endpoints)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+
+    if !errors.is_empty() {
+        return Err(format!("Api endpoints do not match schema: {}", errors.join("; ")).into());
+    }
+
+    Ok(())
+}
+
+pub fn save_api_endpoints(routes: &[RouteObject]) -> Result<(), Box<dyn std::error::Error>> {
+    write_api_endpoints(Path::new(SCHEMA_PATH), routes)
+}
+
+fn write_api_endpoints(
+    path: &Path,
+    routes: &[RouteObject],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let endpoints = serde_json::to_value(routes)?;
+    validate_api_endpoints(&endpoints)?;
 
-    io::copy(&mut reader, &mut writer)?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &endpoints)?;
+    writer.write_all(b"\n")?;
     writer.flush()?;
 
     Ok(())
@@ -116,9 +299,26 @@ fn extract_json_from_code_block(s: &str) -> &str {
     s
 }
 
+// Strips a markdown code fence (```html, ```rust, ...) wrapped around generated code
+pub fn extract_code_from_code_block(s: &str) -> &str {
+    let s = s.trim();
+    if let Some(fenced) = s.strip_prefix("```") {
+        let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+        return body.trim_end().trim_end_matches("```").trim();
+    }
+
+    s
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::ai_functions::aifunc_managing::convert_user_input_to_goal;
+    use crate::{
+        ai_functions::aifunc_managing::convert_user_input_to_goal,
+        models::general::{
+            llm::LlmProvider,
+            schema::{HttpMethod, TypeSchema},
+        },
+    };
 
     use super::*;
 
@@ -142,4 +342,109 @@ mod tests {
 
         assert!(result.len() > 20)
     }
+
+    #[test]
+    fn tests_write_api_endpoints() {
+        let routes = vec![
+            RouteObject::new(
+                HttpMethod::Get,
+                "/task/{id}",
+                None,
+                Some(TypeSchema::Unknown),
+            ),
+            RouteObject::new(
+                HttpMethod::Post,
+                "/task",
+                Some(TypeSchema::Array(Box::new(TypeSchema::Number))),
+                None,
+            ),
+            RouteObject::new(HttpMethod::Head, "/task/{id}", None, None),
+        ];
+
+        let path = std::env::temp_dir().join("rustptty_api_schema_test.json");
+        write_api_endpoints(&path, &routes).expect("Failed to write api endpoints");
+
+        let written: Vec<RouteObject> =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, routes);
+
+        let broken = serde_json::json!([{ "route": "task", "method": "fetch", "is_route_dynamic": "false" }]);
+        assert!(validate_api_endpoints(&broken).is_err());
+    }
+
+    #[test]
+    fn tests_append_run_log() {
+        let path =
+            std::env::temp_dir().join(format!("rustptty_run_log_{}.jsonl", std::process::id()));
+        let choice = ModelChoice {
+            provider: LlmProvider::Anthropic,
+            model: "claude-sonnet-4-5".to_string(),
+            temperature: 0.2,
+            max_tokens: None,
+        };
+        append_run_log(
+            &path,
+            "Backend developer",
+            "print_backend_webserver_code",
+            &choice,
+        )
+        .expect("Failed to write run log");
+        append_run_log(&path, "solutions architect", "print_project_scope", &choice)
+            .expect("Failed to write run log");
+
+        let log = fs::read_to_string(&path).expect("Failed to read run log");
+        let entries = log
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).expect("Invalid log line"))
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["agent"], "Backend developer");
+        assert_eq!(entries[0]["provider"], "anthropic");
+        assert_eq!(entries[0]["model"], "claude-sonnet-4-5");
+        assert_eq!(entries[1]["ai_function"], "print_project_scope");
+        assert!(entries[1]["max_tokens"].is_null());
+
+        fs::remove_file(&path).expect("Failed to clean up run log");
+    }
+
+    #[test]
+    fn tests_write_files_atomically() {
+        let root = std::env::temp_dir().join("rustptty_backend_files_test");
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/old_routes.rs"), "// stale").unwrap();
+        fs::write(root.join("database.json"), "{}").unwrap();
+
+        let mut files = BackendFiles::default();
+        files.insert("Cargo.toml", "[package]\n");
+        files.insert("src/main.rs", "fn main() {}\n");
+        files.insert("src/routes/tasks.rs", "pub fn create() {}\n");
+        write_files_atomically(&root, &files).expect("Failed to write backend files");
+
+        assert_eq!(
+            fs::read_to_string(root.join("src/routes/tasks.rs")).unwrap(),
+            "pub fn create() {}\n"
+        );
+        assert!(root.join("Cargo.toml").exists());
+        assert!(!root.join("src/old_routes.rs").exists());
+        assert!(root.join("database.json").exists());
+        assert!(!root.join(STAGING_DIR).exists());
+        assert!(!root.join(BACKUP_DIR).exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn tests_extract_code_from_code_block() {
+        let fenced = "```html\n<!DOCTYPE html>\n<html></html>\n```";
+        assert_eq!(
+            extract_code_from_code_block(fenced),
+            "<!DOCTYPE html>\n<html></html>"
+        );
+
+        let plain = "  fn main() {}\n";
+        assert_eq!(extract_code_from_code_block(plain), "fn main() {}");
+    }
 }
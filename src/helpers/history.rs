@@ -0,0 +1,256 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+use crate::models::agents::agent_traits::FactSheet;
+
+use super::general::write_files_atomically;
+
+const WORKSPACE_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/web_template/";
+const FACTSHEET_FILE: &str = "factsheet.json";
+const HISTORY_IGNORE: &str = "target/\nCargo.lock\ndatabase.json\ndatabase.db\nartifacts/\nclients/\n.rustptty-staging/\n.rustptty-backup/\n";
+
+// One commit in the workspace history, numbered from 1 for the oldest
+#[derive(Debug, Clone, PartialEq)]
+pub struct Iteration {
+    pub number: usize,
+    pub commit: String,
+    pub message: String,
+}
+
+// Commits the generated project together with the FactSheet that describes it, so any stage
+// can be looked at with git log / git diff and rolled back to later
+pub fn commit_backend_iteration(factsheet: &FactSheet, message: &str) -> io::Result<()> {
+    commit_iteration(Path::new(WORKSPACE_PATH), factsheet, message)
+}
+
+// The FactSheet of the latest iteration, to carry on from where a run stopped
+pub fn load_factsheet() -> Result<FactSheet, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(Path::new(WORKSPACE_PATH).join(FACTSHEET_FILE))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn rollback_backend(number: usize) -> Result<FactSheet, Box<dyn std::error::Error>> {
+    rollback_to(Path::new(WORKSPACE_PATH), number)
+}
+
+// The history repository sits next to the workspace, web_template/ gets .web_template_history/,
+// so no .git ends up inside files the rustptty repository tracks
+fn history_dir(root: &Path) -> PathBuf {
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    root.with_file_name(format!(".{}_history", name))
+}
+
+fn git(root: &Path, args: &[&str]) -> io::Result<Output> {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "user.name=rustptty",
+            "-c",
+            "user.email=rustptty@localhost",
+        ])
+        .arg("--git-dir")
+        .arg(history_dir(root))
+        .arg("--work-tree")
+        .arg(root)
+        .args(args)
+        .current_dir(root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(output)
+}
+
+fn init_history(root: &Path) -> io::Result<()> {
+    let history = history_dir(root);
+    if history.exists() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(root)?;
+    git(root, &["init", "--quiet"])?;
+
+    // Ignored from inside the history repository, the workspace gets no .gitignore of its own
+    fs::create_dir_all(history.join("info"))?;
+    fs::write(history.join("info/exclude"), HISTORY_IGNORE)?;
+
+    Ok(())
+}
+
+fn save_factsheet(root: &Path, factsheet: &FactSheet) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(factsheet).map_err(io::Error::other)?;
+    fs::write(root.join(FACTSHEET_FILE), content)
+}
+
+fn commit_iteration(root: &Path, factsheet: &FactSheet, message: &str) -> io::Result<()> {
+    init_history(root)?;
+    save_factsheet(root, factsheet)?;
+
+    git(root, &["add", "--all"])?;
+    git(root, &["commit", "--quiet", "--allow-empty", "-m", message])?;
+
+    Ok(())
+}
+
+fn list_iterations(root: &Path) -> io::Result<Vec<Iteration>> {
+    // A fresh repository has no HEAD to log from yet
+    if git(root, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+        return Ok(vec![]);
+    }
+
+    let output = git(root, &["log", "--reverse", "--format=%H%x09%s"])?;
+    let iterations = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .enumerate()
+        .map(|(index, (commit, message))| Iteration {
+            number: index + 1,
+            commit: commit.to_string(),
+            message: message.to_string(),
+        })
+        .collect();
+
+    Ok(iterations)
+}
+
+// Puts the backend files of an earlier iteration back into the workspace and the saved
+// FactSheet, then records the rollback as a new iteration
+fn rollback_to(root: &Path, number: usize) -> Result<FactSheet, Box<dyn std::error::Error>> {
+    let iterations = list_iterations(root)?;
+    let iteration = iterations
+        .iter()
+        .find(|iteration| iteration.number == number)
+        .ok_or_else(|| {
+            format!(
+                "No iteration {}, the workspace has {}",
+                number,
+                iterations.len()
+            )
+        })?;
+
+    let object = format!("{}:{}", iteration.commit, FACTSHEET_FILE);
+    let output = git(root, &["show", &object])?;
+    let restored: FactSheet = serde_json::from_slice(&output.stdout)?;
+    let files = restored
+        .backend_files
+        .clone()
+        .ok_or_else(|| format!("Iteration {} has no backend code", number))?;
+
+    let current = fs::read_to_string(root.join(FACTSHEET_FILE))?;
+    let mut factsheet: FactSheet = serde_json::from_str(&current)?;
+    factsheet.backend_files = Some(files.clone());
+
+    write_files_atomically(root, &files)?;
+    let message = format!(
+        "Rollback to iteration {}: {}",
+        iteration.number, iteration.message
+    );
+    commit_iteration(root, &factsheet, &message)?;
+
+    Ok(factsheet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::general::{
+        backend_files::{BackendFiles, MAIN_FILE},
+        edit_mode::EditMode,
+        framework::WebFramework,
+        persistence::PersistenceBackend,
+    };
+
+    fn factsheet(main_code: &str, extra_file: Option<&str>) -> FactSheet {
+        let mut files = BackendFiles::default();
+        files.insert(MAIN_FILE, main_code);
+        if let Some(path) = extra_file {
+            files.insert(path, "pub fn create() {}\n");
+        }
+
+        FactSheet {
+            project_description: "build a website that lets users track their daily tasks"
+                .to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: Some(files),
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        }
+    }
+
+    #[test]
+    fn tests_commit_and_rollback_iterations() {
+        let root = std::env::temp_dir().join(format!("rustptty_history_{}", std::process::id()));
+        for dir in [root.clone(), history_dir(&root)] {
+            if dir.exists() {
+                fs::remove_dir_all(&dir).expect("Failed to clear workspace");
+            }
+        }
+
+        let first = factsheet("fn main() {}\n", None);
+        write_files_atomically(&root, first.backend_files.as_ref().unwrap())
+            .expect("Failed to write files");
+        fs::create_dir_all(root.join("artifacts")).expect("Failed to create artifacts");
+        fs::write(root.join("artifacts/run_log.jsonl"), "{}\n").expect("Failed to write log");
+        commit_iteration(&root, &first, "Backend developer: initial").expect("Failed to commit");
+
+        assert!(!root.join(".git").exists());
+        let tracked = git(&root, &["ls-files"]).expect("Failed to list files");
+        assert_eq!(
+            String::from_utf8_lossy(&tracked.stdout),
+            "factsheet.json\nsrc/main.rs\n"
+        );
+
+        let second = factsheet("fn main() { broken }\n", Some("src/routes.rs"));
+        write_files_atomically(&root, second.backend_files.as_ref().unwrap())
+            .expect("Failed to write files");
+        commit_iteration(&root, &second, "Backend developer: improved").expect("Failed to commit");
+
+        let restored = rollback_to(&root, 1).expect("Failed to roll back");
+
+        assert_eq!(restored.backend_files, first.backend_files);
+        assert_eq!(
+            fs::read_to_string(root.join(MAIN_FILE)).ok().as_deref(),
+            Some("fn main() {}\n")
+        );
+        assert!(!root.join("src/routes.rs").exists());
+
+        let iterations = list_iterations(&root).expect("Failed to list iterations");
+        assert_eq!(
+            iterations
+                .iter()
+                .map(|iteration| iteration.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Backend developer: initial",
+                "Backend developer: improved",
+                "Rollback to iteration 1: Backend developer: initial",
+            ]
+        );
+        assert!(rollback_to(&root, 9).is_err());
+
+        fs::remove_dir_all(&root).expect("Failed to clean up workspace");
+        fs::remove_dir_all(history_dir(&root)).expect("Failed to clean up history");
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fs, io, path::Path};
+
+use crate::models::general::{
+    backend_files::{BackendFiles, MAIN_FILE, MANIFEST_FILE},
+    framework::WebFramework,
+    persistence::PersistenceBackend,
+};
+
+// Reads Cargo.toml and every Rust source under src/ of an existing crate. Anything else it has,
+// such as static files or tests/, is outside the generated project layout and left behind
+pub fn read_crate_files(crate_path: &Path) -> Result<BackendFiles, Box<dyn std::error::Error>> {
+    let manifest = fs::read_to_string(crate_path.join(MANIFEST_FILE))?;
+
+    let mut files = BackendFiles::default();
+    files.insert(MANIFEST_FILE, &manifest);
+    collect_sources(crate_path, Path::new("src"), &mut files)?;
+
+    if files.get(MAIN_FILE).is_none() {
+        return Err(format!(
+            "{} has no {}, only binary crates can be imported",
+            crate_path.display(),
+            MAIN_FILE
+        )
+        .into());
+    }
+
+    Ok(files)
+}
+
+fn collect_sources(root: &Path, relative: &Path, files: &mut BackendFiles) -> io::Result<()> {
+    let mut entries = fs::read_dir(root.join(relative))?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            collect_sources(root, &path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            let key = path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(&key, &fs::read_to_string(root.join(&path))?);
+        }
+    }
+
+    Ok(())
+}
+
+// Names of the crates under [dependencies], e.g. "axum" from axum = { version = "0.8" }
+fn dependency_names(manifest: &str) -> Vec<&str> {
+    let mut names = vec![];
+    let mut in_dependencies = false;
+
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_dependencies = line == "[dependencies]";
+            continue;
+        }
+
+        if let Some((name, _)) = line.split_once('=').filter(|_| in_dependencies) {
+            names.push(name.trim().trim_matches('"'));
+        }
+    }
+
+    names
+}
+
+pub fn detect_framework(manifest: &str) -> Option<WebFramework> {
+    dependency_names(manifest)
+        .into_iter()
+        .find_map(|name| match name {
+            "actix-web" => Some(WebFramework::Actix),
+            "axum" => Some(WebFramework::Axum),
+            "rocket" => Some(WebFramework::Rocket),
+            _ => None,
+        })
+}
+
+// sqlx with the postgres or sqlite feature, otherwise the data is taken to live in a file
+pub fn detect_persistence(manifest: &str) -> PersistenceBackend {
+    let Some(sqlx) = manifest
+        .lines()
+        .find(|line| line.trim_start().starts_with("sqlx"))
+    else {
+        return PersistenceBackend::JsonFile;
+    };
+
+    if sqlx.contains("\"postgres\"") {
+        PersistenceBackend::Postgres
+    } else if sqlx.contains("\"sqlite\"") {
+        PersistenceBackend::Sqlite
+    } else {
+        PersistenceBackend::JsonFile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "[package]\nname = \"tasks\"\n\n[dependencies]\n\
+        serde = { version = \"1\", features = [\"derive\"] }\n\
+        axum = \"0.8\"\n\
+        sqlx = { version = \"0.8\", features = [\"runtime-tokio\", \"sqlite\"] }\n\n\
+        [dev-dependencies]\nrocket = \"0.5\"\n";
+
+    #[test]
+    fn tests_read_crate_files() {
+        let root = std::env::temp_dir().join(format!("rustptty_import_{}", std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root).expect("Failed to clear crate");
+        }
+        fs::create_dir_all(root.join("src/routes")).expect("Failed to create crate");
+        fs::create_dir_all(root.join("static")).expect("Failed to create crate");
+        fs::write(root.join(MANIFEST_FILE), MANIFEST).expect("Failed to write crate");
+        fs::write(root.join(MAIN_FILE), "mod routes;\nfn main() {}\n").expect("Failed to write");
+        fs::write(root.join("src/routes/mod.rs"), "pub mod tasks;\n").expect("Failed to write");
+        fs::write(root.join("src/routes/tasks.rs"), "pub fn create() {}\n").expect("Failed");
+        fs::write(root.join("src/notes.md"), "not code").expect("Failed to write crate");
+        fs::write(root.join("static/index.html"), "<html></html>").expect("Failed to write");
+
+        let files = read_crate_files(&root).expect("Failed to read crate");
+
+        assert_eq!(
+            files.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+            vec![
+                MANIFEST_FILE,
+                MAIN_FILE,
+                "src/routes/mod.rs",
+                "src/routes/tasks.rs"
+            ]
+        );
+        assert_eq!(detect_framework(MANIFEST), Some(WebFramework::Axum));
+        assert_eq!(detect_persistence(MANIFEST), PersistenceBackend::Sqlite);
+        assert_eq!(detect_framework("[dependencies]\nwarp = \"0.3\"\n"), None);
+
+        fs::remove_file(root.join(MAIN_FILE)).expect("Failed to remove main.rs");
+        assert!(read_crate_files(&root).is_err());
+
+        fs::remove_dir_all(&root).expect("Failed to clean up crate");
+    }
+}
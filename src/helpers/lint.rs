@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use serde_yaml::Value;
+
+use crate::models::general::persistence::PersistenceBackend;
+
+const DOCKERFILE_INSTRUCTIONS: [&str; 18] = [
+    "FROM",
+    "RUN",
+    "CMD",
+    "LABEL",
+    "MAINTAINER",
+    "EXPOSE",
+    "ENV",
+    "ADD",
+    "COPY",
+    "ENTRYPOINT",
+    "VOLUME",
+    "USER",
+    "WORKDIR",
+    "ARG",
+    "ONBUILD",
+    "STOPSIGNAL",
+    "HEALTHCHECK",
+    "SHELL",
+];
+
+// Lints a generated DevOps file by name, returning one message per problem found
+pub fn lint_devops_file(
+    file_name: &str,
+    content: &str,
+    persistence: PersistenceBackend,
+) -> Vec<String> {
+    match file_name {
+        "Dockerfile" => lint_dockerfile(content),
+        "docker-compose.yml" => lint_docker_compose(content),
+        ".env.example" => lint_env_example(content, persistence),
+        "Makefile" => lint_makefile(content),
+        _ => vec![format!("No linter for {}", file_name)],
+    }
+}
+
+// Joins backslash continued lines and drops comments and blank lines
+fn dockerfile_instructions(content: &str) -> Vec<String> {
+    let mut instructions = vec![];
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if current.is_empty() && (trimmed.is_empty() || trimmed.starts_with('#')) {
+            continue;
+        }
+
+        match trimmed.strip_suffix('\\') {
+            Some(continued) => {
+                current.push_str(continued);
+                current.push(' ');
+            }
+            None => {
+                current.push_str(trimmed);
+                instructions.push(std::mem::take(&mut current));
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        instructions.push(current);
+    }
+
+    instructions
+}
+
+fn lint_dockerfile(content: &str) -> Vec<String> {
+    let mut errors = vec![];
+    let mut stages: Vec<String> = vec![];
+    let mut has_start_command = false;
+
+    for (index, instruction) in dockerfile_instructions(content).iter().enumerate() {
+        let mut parts = instruction.split_whitespace();
+        let keyword = parts.next().unwrap_or_default().to_uppercase();
+        let args: Vec<&str> = parts.collect();
+
+        if !DOCKERFILE_INSTRUCTIONS.contains(&keyword.as_str()) {
+            errors.push(format!("Unknown instruction: {}", instruction));
+            continue;
+        }
+
+        if index == 0 && keyword != "FROM" && keyword != "ARG" {
+            errors.push("Dockerfile must start with FROM".to_string());
+        }
+
+        match keyword.as_str() {
+            "FROM" => {
+                let alias = match args.as_slice() {
+                    [_, as_kw, alias] if as_kw.eq_ignore_ascii_case("as") => alias.to_string(),
+                    _ => stages.len().to_string(),
+                };
+                stages.push(alias);
+            }
+            "COPY" => {
+                if let Some(stage) = args.iter().find_map(|arg| arg.strip_prefix("--from=")) {
+                    if !stages.iter().any(|known| known == stage) {
+                        errors.push(format!("COPY --from references unknown stage {}", stage));
+                    }
+                }
+            }
+            "CMD" | "ENTRYPOINT" => has_start_command = true,
+            _ => {}
+        }
+    }
+
+    if stages.len() < 2 {
+        errors.push("Dockerfile must be a multi-stage build with at least two FROM".to_string());
+    }
+
+    if !has_start_command {
+        errors.push("Dockerfile has no CMD or ENTRYPOINT".to_string());
+    }
+
+    errors
+}
+
+fn lint_docker_compose(content: &str) -> Vec<String> {
+    let compose: Value = match serde_yaml::from_str(content) {
+        Ok(compose) => compose,
+        Err(e) => return vec![format!("Invalid YAML: {}", e)],
+    };
+
+    let mut errors = vec![];
+
+    let Some(services) = compose.get("services").and_then(Value::as_mapping) else {
+        return vec!["Missing top level services".to_string()];
+    };
+
+    if services.is_empty() {
+        errors.push("No services defined".to_string());
+    }
+
+    let declared_volumes: HashSet<String> = compose
+        .get("volumes")
+        .and_then(Value::as_mapping)
+        .map(|volumes| {
+            volumes
+                .keys()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut mounts_database_volume = false;
+
+    for (name, service) in services {
+        let name = name.as_str().unwrap_or("<unnamed>");
+
+        if service.get("build").is_none() && service.get("image").is_none() {
+            errors.push(format!("Service {} needs a build or image", name));
+        }
+
+        let volumes = service
+            .get("volumes")
+            .and_then(Value::as_sequence)
+            .cloned()
+            .unwrap_or_default();
+
+        for volume in volumes.iter().filter_map(Value::as_str) {
+            let source = volume.split(':').next().unwrap_or_default();
+            let is_named = !source.starts_with('.') && !source.starts_with('/');
+
+            if is_named && !declared_volumes.contains(source) {
+                errors.push(format!(
+                    "Service {} uses volume {} which is not declared under top level volumes",
+                    name, source
+                ));
+            }
+
+            if is_named && volume.contains(":/app/data") {
+                mounts_database_volume = true;
+            }
+        }
+    }
+
+    if !mounts_database_volume {
+        errors.push("No named volume mounted at /app/data for the JSON database".to_string());
+    }
+
+    errors
+}
+
+fn lint_env_example(content: &str, persistence: PersistenceBackend) -> Vec<String> {
+    let mut errors = vec![];
+    let mut keys = HashSet::new();
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, _)) = line.split_once('=') else {
+            errors.push(format!("Line is not KEY=value: {}", line));
+            continue;
+        };
+
+        let is_upper_snake = key.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+        if !is_upper_snake {
+            errors.push(format!("Key {} is not UPPER_SNAKE_CASE", key));
+        }
+
+        if !keys.insert(key.to_string()) {
+            errors.push(format!("Duplicate key {}", key));
+        }
+    }
+
+    for required in ["SERVER_ADDRESS", persistence.database_env_var()] {
+        if !keys.contains(required) {
+            errors.push(format!("Missing {}", required));
+        }
+    }
+
+    errors
+}
+
+fn lint_makefile(content: &str) -> Vec<String> {
+    let mut errors = vec![];
+    let mut targets = HashSet::new();
+    let mut in_rule = false;
+
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('\t') {
+            if !in_rule {
+                errors.push(format!("Line {}: recipe outside of a rule", number + 1));
+            }
+            continue;
+        }
+
+        if line.starts_with(' ') {
+            errors.push(format!(
+                "Line {}: recipe lines must start with a TAB, not spaces",
+                number + 1
+            ));
+            continue;
+        }
+
+        in_rule = false;
+        if let Some((target, _)) = line.split_once(':') {
+            if !target.contains('=') {
+                in_rule = true;
+                targets.extend(target.split_whitespace().map(str::to_string));
+            }
+        }
+    }
+
+    for required in ["build", "run", "test"] {
+        if !targets.contains(required) {
+            errors.push(format!("Missing {} target", required));
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_lint_dockerfile() {
+        let dockerfile = "FROM rust:1.86 AS builder\nWORKDIR /app\nCOPY . .\nRUN cargo build \\\n    --release\n\nFROM debian:bookworm-slim\nCOPY --from=builder /app/target/release/web_template /app/web_template\nCMD [\"/app/web_template\"]\n";
+        assert!(lint_dockerfile(dockerfile).is_empty());
+
+        let single_stage = "FROM rust:1.86\nCOPY --from=build /a /b\nRUNN cargo build\n";
+        assert_eq!(lint_dockerfile(single_stage).len(), 4);
+    }
+
+    #[test]
+    fn tests_lint_docker_compose() {
+        let compose = "services:\n  web:\n    build: .\n    ports:\n      - \"8080:8080\"\n    volumes:\n      - db_data:/app/data\nvolumes:\n  db_data:\n";
+        assert!(lint_docker_compose(compose).is_empty());
+
+        let undeclared =
+            "services:\n  web:\n    build: .\n    volumes:\n      - db_data:/app/data\n";
+        assert_eq!(lint_docker_compose(undeclared).len(), 1);
+    }
+
+    #[test]
+    fn tests_lint_env_example_and_makefile() {
+        let env = "# Server\nSERVER_ADDRESS=0.0.0.0:8080\nDATABASE_PATH=/app/data/database.json\n";
+        assert!(lint_env_example(env, PersistenceBackend::JsonFile).is_empty());
+        assert_eq!(
+            lint_env_example(env, PersistenceBackend::Postgres),
+            vec!["Missing DATABASE_URL"]
+        );
+        assert_eq!(
+            lint_env_example("server_address=1\n", PersistenceBackend::JsonFile).len(),
+            3
+        );
+
+        let makefile = ".PHONY: build run test\nbuild:\n\tcargo build\nrun:\n\tcargo run\ntest:\n\tcargo test\n";
+        assert!(lint_makefile(makefile).is_empty());
+
+        let spaces = "build:\n    cargo build\nrun:\n\tcargo run\ntest:\n\tcargo test\n";
+        assert_eq!(lint_makefile(spaces).len(), 1);
+    }
+}
@@ -1,2 +1,9 @@
 pub mod cli;
 pub mod general;
+pub mod history;
+pub mod importer;
+pub mod lint;
+pub mod patcher;
+pub mod route_extractor;
+pub mod templates;
+pub mod web_server;
@@ -0,0 +1,361 @@
+use std::ops::Range;
+
+use crate::models::general::backend_files::{is_allowed_path, BackendFiles};
+
+const PATCH_START: &str = "=== PATCH:";
+const PATCH_DELIMITER: &str = "===";
+const PATCH_END: &str = "=== END PATCH ===";
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER_MARKER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+// Average line similarity a drifted SEARCH block still needs to match
+const FUZZY_THRESHOLD: f64 = 0.85;
+
+// Edits to one generated file. The LLM writes them as:
+//   === PATCH: src/routes/tasks.rs ===
+//   <<<<<<< SEARCH
+//   lines to find
+//   =======
+//   lines to put in their place
+//   >>>>>>> REPLACE
+//   === END PATCH ===
+// An empty SEARCH block creates the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePatch {
+    pub path: String,
+    pub edits: Vec<SearchReplace>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchReplace {
+    pub search: String,
+    pub replace: String,
+}
+
+pub fn parse_patches(output: &str) -> Result<Vec<FilePatch>, String> {
+    let mut patches: Vec<FilePatch> = vec![];
+    let mut lines = output.lines();
+    let mut is_open = false;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if let Some(path) = trimmed
+            .strip_prefix(PATCH_START)
+            .and_then(|rest| rest.strip_suffix(PATCH_DELIMITER))
+        {
+            patches.push(FilePatch {
+                path: path.trim().to_string(),
+                edits: vec![],
+            });
+            is_open = true;
+            continue;
+        }
+
+        if trimmed == PATCH_END {
+            is_open = false;
+            continue;
+        }
+
+        if trimmed == SEARCH_MARKER {
+            let patch = patches
+                .last_mut()
+                .filter(|_| is_open)
+                .ok_or("SEARCH block without a PATCH header")?;
+            let search = collect_block(&mut lines, DIVIDER_MARKER)?;
+            let replace = collect_block(&mut lines, REPLACE_MARKER)?;
+            patch.edits.push(SearchReplace { search, replace });
+        }
+
+        // Code fences and commentary between blocks are skipped. A missing END PATCH is
+        // forgiven, the next header starts the next file
+    }
+
+    if patches.is_empty() {
+        return Err("No patches found in the output".to_string());
+    }
+    if let Some(patch) = patches.iter().find(|patch| patch.edits.is_empty()) {
+        return Err(format!(
+            "Patch for {} has no SEARCH/REPLACE blocks",
+            patch.path
+        ));
+    }
+
+    Ok(patches)
+}
+
+fn collect_block<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    marker: &str,
+) -> Result<String, String> {
+    let mut block = vec![];
+
+    for line in lines.by_ref() {
+        if line.trim() == marker {
+            return Ok(block.join("\n"));
+        }
+        block.push(line);
+    }
+
+    Err(format!("Missing {} marker", marker))
+}
+
+// Applies the patches to a copy of the files, so nothing changes unless every edit applies
+pub fn apply_patches(files: &BackendFiles, patches: &[FilePatch]) -> Result<BackendFiles, String> {
+    let mut patched = files.clone();
+
+    for patch in patches {
+        if !is_allowed_path(&patch.path) {
+            return Err(format!(
+                "Patched file {} is outside Cargo.toml and src/",
+                patch.path
+            ));
+        }
+
+        let mut content = patched.get(&patch.path).map(String::from);
+        for edit in &patch.edits {
+            let edited = apply_edit(content.as_deref(), edit)
+                .map_err(|error| format!("{}: {}", patch.path, error))?;
+            content = Some(edited);
+        }
+
+        if let Some(content) = content {
+            patched.insert(&patch.path, &content);
+        }
+    }
+
+    Ok(patched)
+}
+
+fn apply_edit(content: Option<&str>, edit: &SearchReplace) -> Result<String, String> {
+    let search = trim_blank_lines(edit.search.lines().collect());
+
+    if search.is_empty() {
+        return match content {
+            Some(_) => Err("empty SEARCH block for a file that already exists".to_string()),
+            None => Ok(format!("{}\n", edit.replace.trim_end())),
+        };
+    }
+
+    let content = content.ok_or("file does not exist")?;
+    let lines = content.lines().collect::<Vec<_>>();
+    let found = find_block(&lines, &search)?;
+
+    let mut edited = lines[..found.start].to_vec();
+    edited.extend(edit.replace.lines());
+    edited.extend(&lines[found.end..]);
+
+    Ok(edited.join("\n") + "\n")
+}
+
+fn trim_blank_lines(mut lines: Vec<&str>) -> Vec<&str> {
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let leading = lines
+        .iter()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    lines.split_off(leading)
+}
+
+// Tries exact lines, then lines without indentation, then the closest window of as many
+// lines. Whichever way it matches, the block must match one place only
+fn find_block(lines: &[&str], search: &[&str]) -> Result<Range<usize>, String> {
+    if search.len() > lines.len() {
+        return Err("SEARCH block is longer than the file".to_string());
+    }
+
+    let starts = 0..=lines.len() - search.len();
+    let window = |start: usize| search.iter().zip(&lines[start..]);
+
+    let comparisons: [fn(&str, &str) -> bool; 2] = [
+        |search, line| search.trim_end() == line.trim_end(),
+        |search, line| search.trim() == line.trim(),
+    ];
+    for same in comparisons {
+        let matches = starts
+            .clone()
+            .filter(|&start| window(start).all(|(search, line)| same(search, line)))
+            .collect::<Vec<_>>();
+
+        match matches.as_slice() {
+            [] => continue,
+            [start] => return Ok(*start..*start + search.len()),
+            _ => return Err("SEARCH block matches more than one place".to_string()),
+        }
+    }
+
+    let mut best: Option<(f64, usize)> = None;
+    let mut is_ambiguous = false;
+    for start in starts {
+        let score = window(start)
+            .map(|(search, line)| similarity(search.trim(), line.trim()))
+            .sum::<f64>()
+            / search.len() as f64;
+
+        match best {
+            Some((best_score, _)) if score < best_score => {}
+            Some((best_score, _)) if score == best_score => is_ambiguous = true,
+            _ => {
+                best = Some((score, start));
+                is_ambiguous = false;
+            }
+        }
+    }
+
+    match best {
+        Some((score, _)) if score >= FUZZY_THRESHOLD && is_ambiguous => {
+            Err("SEARCH block matches more than one place".to_string())
+        }
+        Some((score, start)) if score >= FUZZY_THRESHOLD => Ok(start..start + search.len()),
+        _ => Err("SEARCH block not found".to_string()),
+    }
+}
+
+// 1.0 for equal lines down to 0.0, from the edit distance between them
+fn similarity(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::general::backend_files::MAIN_FILE;
+
+    const TASKS_FILE: &str = "src/routes/tasks.rs";
+
+    const TASKS_CODE: &str = "pub async fn create(Json(task): Json<Task>) -> HttpResponse {\n    \
+        db.insert(task);\n    \
+        HttpResponse::Ok().body(\"Task created\")\n\
+        }\n\
+        \n\
+        pub async fn delete(id: Path<u64>) -> HttpResponse {\n    \
+        HttpResponse::Ok().finish()\n\
+        }\n";
+
+    fn project() -> BackendFiles {
+        let mut files = BackendFiles::default();
+        files.insert(MAIN_FILE, "mod routes;\nfn main() {}\n");
+        files.insert(TASKS_FILE, TASKS_CODE);
+        files
+    }
+
+    fn patch(path: &str, search: &str, replace: &str) -> FilePatch {
+        FilePatch {
+            path: path.to_string(),
+            edits: vec![SearchReplace {
+                search: search.to_string(),
+                replace: replace.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn tests_parse_patches() {
+        let output = "Two changes:\n\
+            ```\n\
+            === PATCH: src/routes/tasks.rs ===\n\
+            <<<<<<< SEARCH\n\
+            db.insert(task);\n\
+            =======\n\
+            db.insert(task.clone());\n\
+            >>>>>>> REPLACE\n\
+            <<<<<<< SEARCH\n\
+            Task created\n\
+            =======\n\
+            >>>>>>> REPLACE\n\
+            === END PATCH ===\n\
+            === PATCH: src/db.rs ===\n\
+            <<<<<<< SEARCH\n\
+            =======\n\
+            pub struct Db;\n\
+            >>>>>>> REPLACE\n\
+            ```\n";
+
+        let patches = parse_patches(output).expect("Failed to parse patches");
+
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0].path, TASKS_FILE);
+        assert_eq!(
+            patches[0].edits,
+            vec![
+                SearchReplace {
+                    search: "db.insert(task);".to_string(),
+                    replace: "db.insert(task.clone());".to_string(),
+                },
+                SearchReplace {
+                    search: "Task created".to_string(),
+                    replace: String::new(),
+                },
+            ]
+        );
+        assert_eq!(patches[1], patch("src/db.rs", "", "pub struct Db;"));
+
+        assert!(parse_patches("fn main() {}").is_err());
+        assert!(parse_patches("<<<<<<< SEARCH\na\n=======\nb\n>>>>>>> REPLACE").is_err());
+        assert!(
+            parse_patches("=== PATCH: src/main.rs ===\n<<<<<<< SEARCH\na\n=======\nb").is_err()
+        );
+        assert!(parse_patches("=== PATCH: src/main.rs ===\n=== END PATCH ===").is_err());
+        assert!(parse_patches(
+            "=== PATCH: src/main.rs ===\n=== END PATCH ===\n<<<<<<< SEARCH\na\n=======\nb\n>>>>>>> REPLACE"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn tests_apply_patches() {
+        let files = project();
+
+        // Indentation drift and a reworded line still find their place
+        let patches = vec![
+            patch(
+                TASKS_FILE,
+                "db.insert(task);",
+                "    db.insert(task.clone());",
+            ),
+            patch(
+                TASKS_FILE,
+                "    HttpResponse::Ok().body(\"The task was created\")\n}",
+                "    HttpResponse::Created().json(task)\n}",
+            ),
+            patch("src/db.rs", "", "pub struct Db;"),
+        ];
+        let patched = apply_patches(&files, &patches).expect("Failed to apply patches");
+
+        assert!(patched.get(TASKS_FILE).is_some_and(|code| code.starts_with(
+            "pub async fn create(Json(task): Json<Task>) -> HttpResponse {\n    \
+            db.insert(task.clone());\n    \
+            HttpResponse::Created().json(task)\n}\n\npub async fn delete"
+        )));
+        assert_eq!(patched.get("src/db.rs"), Some("pub struct Db;\n"));
+        assert_eq!(patched.get(MAIN_FILE), files.get(MAIN_FILE));
+        assert_eq!(files, project());
+
+        let fails = |patch: FilePatch| apply_patches(&files, &[patch]).is_err();
+        assert!(fails(patch(TASKS_FILE, "db.remove(id);", "")));
+        assert!(fails(patch(TASKS_FILE, "}", "")));
+        assert!(fails(patch(TASKS_FILE, "", "pub struct Db;")));
+        assert!(fails(patch("src/models.rs", "struct Task;", "")));
+        assert!(fails(patch("../build.rs", "", "fn main() {}")));
+    }
+}
@@ -0,0 +1,1111 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use syn::{
+    parse::{ParseStream, Parser},
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    Expr, ExprCall, ExprMethodCall, Fields, FnArg, GenericArgument, ItemFn, ItemMod, ItemStruct,
+    Lit, LitStr, Meta, Pat, PathArguments, ReturnType, Token, Type,
+};
+
+use crate::models::{
+    agents::agent_traits::RouteObject,
+    general::{
+        framework::WebFramework,
+        schema::{HttpMethod, TypeSchema},
+    },
+};
+
+const MAX_TYPE_DEPTH: u8 = 8;
+
+// Reads routes out of generated code without an LLM:
+//   actix: .route("/x", web::get().to(h)), .service(web::resource(..)), .service(web::scope(..)) and #[get("/x")]
+//   axum: .route("/x", get(h).post(h2)), .nest("/api", router) and .merge(router)
+//   rocket: .mount("/", routes![h]) with #[get("/x/<id>")] handlers
+// The project may be split over several files, given as (path, code) pairs such as
+// ("src/routes/tasks.rs", ...). Handlers named the same in two modules are told apart by the
+// path they are registered with, like tasks::create
+pub fn extract_routes_from_sources<'a>(
+    sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+    framework: WebFramework,
+) -> Result<Vec<RouteObject>, syn::Error> {
+    let mut files = vec![];
+    for (path, code) in sources {
+        files.push((module_path(path), syn::parse_file(code)?));
+    }
+
+    let mut items = ItemCollector::default();
+    let mut app_chains = AppChainCollector {
+        roots: app_roots(framework),
+        current_fn: None,
+        chains: vec![],
+    };
+    for (module, file) in &files {
+        items.module = module.clone();
+        items.visit_file(file);
+        app_chains.visit_file(file);
+    }
+
+    // Routers built by helper functions are walked where they get nested or merged
+    let mut router_fns = HashMap::new();
+    for (enclosing_fn, chain) in &app_chains.chains {
+        if let Some(enclosing_fn) = enclosing_fn {
+            router_fns.entry(enclosing_fn.clone()).or_insert(*chain);
+        }
+    }
+    let nested_fns: HashSet<String> = app_chains
+        .chains
+        .iter()
+        .flat_map(|(_, chain)| nested_router_fns(chain))
+        .collect();
+
+    let extractor = RouteExtractor {
+        framework,
+        structs: items.structs,
+        handlers: items.handlers,
+        router_fns,
+    };
+
+    let mut routes = vec![];
+    for (enclosing_fn, chain) in &app_chains.chains {
+        if enclosing_fn
+            .as_ref()
+            .is_some_and(|name| nested_fns.contains(name))
+        {
+            continue;
+        }
+        extractor.walk_chain(chain, "", &mut routes);
+    }
+
+    retain_api_routes(&mut routes);
+
+    Ok(routes)
+}
+
+// OPTIONS handlers answer CORS preflights, like rocket's #[options("/<_..>")] catch-all,
+// they are not part of the API
+pub fn retain_api_routes(routes: &mut Vec<RouteObject>) {
+    routes.retain(|route| route.method != HttpMethod::Options);
+}
+
+// src/routes/tasks.rs is the module routes::tasks, main.rs, lib.rs and mod.rs name their folder
+fn module_path(file_path: &str) -> Vec<String> {
+    let path = file_path.strip_prefix("src/").unwrap_or(file_path);
+    let path = path.strip_suffix(".rs").unwrap_or(path);
+
+    let mut module: Vec<String> = path.split('/').map(str::to_string).collect();
+    if matches!(
+        module.last().map(String::as_str),
+        Some("main" | "lib" | "mod")
+    ) {
+        module.pop();
+    }
+
+    module
+}
+
+// Calls that start the app builder chain for each framework
+fn app_roots(framework: WebFramework) -> &'static [&'static str] {
+    match framework {
+        WebFramework::Actix => &["App::new"],
+        WebFramework::Axum => &["Router::new"],
+        WebFramework::Rocket => &["rocket::build", "rocket::custom"],
+    }
+}
+
+// Names of the functions a chain nests or merges, as in .nest("/api", task_routes())
+fn nested_router_fns(chain: &Expr) -> Vec<String> {
+    let mut names = vec![];
+    let mut current = chain;
+
+    while let Expr::MethodCall(call) = current {
+        if call.method == "nest" || call.method == "merge" {
+            if let Some(name) = call.args.last().and_then(path_of_call) {
+                names.push(last_segment(&name).to_string());
+            }
+        }
+        current = &call.receiver;
+    }
+
+    names
+}
+
+// Handlers are kept by name along with the module they are defined in
+#[derive(Default)]
+struct ItemCollector<'ast> {
+    module: Vec<String>,
+    structs: HashMap<String, &'ast ItemStruct>,
+    handlers: HashMap<String, Vec<(Vec<String>, &'ast ItemFn)>>,
+}
+
+impl<'ast> Visit<'ast> for ItemCollector<'ast> {
+    fn visit_item_struct(&mut self, item: &'ast ItemStruct) {
+        self.structs.insert(item.ident.to_string(), item);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        self.handlers
+            .entry(item.sig.ident.to_string())
+            .or_default()
+            .push((self.module.clone(), item));
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        self.module.push(item.ident.to_string());
+        visit::visit_item_mod(self, item);
+        self.module.pop();
+    }
+}
+
+// Finds the outermost method chains that start at App::new(), Router::new() or rocket::build(),
+// along with the function they are written in
+struct AppChainCollector<'ast> {
+    roots: &'static [&'static str],
+    current_fn: Option<String>,
+    chains: Vec<(Option<String>, &'ast Expr)>,
+}
+
+impl<'ast> Visit<'ast> for AppChainCollector<'ast> {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        let outer_fn = self.current_fn.replace(item.sig.ident.to_string());
+        visit::visit_item_fn(self, item);
+        self.current_fn = outer_fn;
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        let is_app_chain = matches!(expr, Expr::MethodCall(_))
+            && path_of_call(chain_root(expr))
+                .is_some_and(|path| self.roots.iter().any(|root| path.ends_with(root)));
+
+        if is_app_chain {
+            self.chains.push((self.current_fn.clone(), expr));
+            return;
+        }
+
+        visit::visit_expr(self, expr);
+    }
+}
+
+fn chain_root(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::MethodCall(call) => chain_root(&call.receiver),
+        Expr::Paren(paren) => chain_root(&paren.expr),
+        other => other,
+    }
+}
+
+fn expr_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(path) => Some(
+            path.path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::"),
+        ),
+        _ => None,
+    }
+}
+
+// "web::resource" for web::resource("/x"), "App::new" for App::new()
+fn path_of_call(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call(ExprCall { func, .. }) => expr_path(func),
+        _ => None,
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn string_arg(expr: Option<&Expr>) -> Option<String> {
+    match expr? {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(value) => Some(value.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn join_route(prefix: &str, path: &str) -> String {
+    let joined = format!(
+        "{}/{}",
+        prefix.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    if joined.len() > 1 {
+        joined.trim_end_matches('/').to_string()
+    } else {
+        joined
+    }
+}
+
+// Rewrites rocket <id>, <path..> and axum 0.7 :id, *rest segments as {id}
+fn normalize_path_params(route: &str) -> String {
+    route
+        .split('/')
+        .map(|segment| {
+            if let Some(param) = segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                format!("{{{}}}", param.trim_end_matches(".."))
+            } else if let Some(param) = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('*'))
+            {
+                format!("{{{}}}", param)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn pat_binds(pat: &Pat, name: &str) -> bool {
+    match pat {
+        Pat::Ident(ident) => ident.ident == name,
+        Pat::TupleStruct(tuple) => tuple.elems.iter().any(|elem| pat_binds(elem, name)),
+        _ => false,
+    }
+}
+
+struct RouteExtractor<'ast> {
+    framework: WebFramework,
+    structs: HashMap<String, &'ast ItemStruct>,
+    handlers: HashMap<String, Vec<(Vec<String>, &'ast ItemFn)>>,
+    router_fns: HashMap<String, &'ast Expr>,
+}
+
+impl<'ast> RouteExtractor<'ast> {
+    // Resolves a handler path such as tasks::create, falling back to the first one of that name
+    fn handler(&self, path: &str) -> Option<&'ast ItemFn> {
+        let candidates = self.handlers.get(last_segment(path))?;
+        let qualifier: Vec<&str> = path
+            .split("::")
+            .filter(|segment| !matches!(*segment, "crate" | "self" | "super"))
+            .collect();
+        let qualifier = &qualifier[..qualifier.len().saturating_sub(1)];
+
+        candidates
+            .iter()
+            .find(|(module, _)| {
+                module.len() >= qualifier.len()
+                    && module[module.len() - qualifier.len()..]
+                        .iter()
+                        .zip(qualifier)
+                        .all(|(a, b)| a == b)
+            })
+            .or(candidates.first())
+            .map(|(_, item)| *item)
+    }
+
+    fn walk_chain(&self, expr: &Expr, prefix: &str, routes: &mut Vec<RouteObject>) {
+        let Expr::MethodCall(call) = expr else {
+            return;
+        };
+
+        self.walk_chain(&call.receiver, prefix, routes);
+
+        let args: Vec<&Expr> = call.args.iter().collect();
+        match (
+            self.framework,
+            call.method.to_string().as_str(),
+            args.as_slice(),
+        ) {
+            (WebFramework::Actix, "route", [path, handler]) => {
+                if let Some(path) = string_arg(Some(path)) {
+                    self.push_route(&join_route(prefix, &path), handler, routes);
+                }
+            }
+            (WebFramework::Actix, "service", [service]) => {
+                self.walk_service(service, prefix, routes)
+            }
+            (WebFramework::Axum, "route", [path, method_router]) => {
+                if let Some(path) = string_arg(Some(path)) {
+                    self.walk_method_router(&join_route(prefix, &path), method_router, routes);
+                }
+            }
+            (WebFramework::Axum, "nest", [path, router]) => {
+                if let Some(path) = string_arg(Some(path)) {
+                    self.walk_router(router, &join_route(prefix, &path), routes);
+                }
+            }
+            (WebFramework::Axum, "merge", [router]) => self.walk_router(router, prefix, routes),
+            (WebFramework::Rocket, "mount", [base, handlers]) => {
+                if let Some(base) = string_arg(Some(base)) {
+                    self.walk_mounted_routes(handlers, &join_route(prefix, &base), routes);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // get(list).post(create), where each method function takes the handler
+    fn walk_method_router(&self, path: &str, expr: &Expr, routes: &mut Vec<RouteObject>) {
+        let (method, handler) = match expr {
+            Expr::MethodCall(call) => {
+                self.walk_method_router(path, &call.receiver, routes);
+                (call.method.to_string(), call.args.first())
+            }
+            Expr::Call(call) => (expr_path(&call.func).unwrap_or_default(), call.args.first()),
+            _ => return,
+        };
+
+        let Ok(http_method) = last_segment(&method).parse::<HttpMethod>() else {
+            return;
+        };
+        let Some(handler) = handler.and_then(expr_path) else {
+            return;
+        };
+
+        routes.push(self.build_route(http_method, path, &handler));
+    }
+
+    // Router::new()... written inline, or a call to a function that builds one
+    fn walk_router(&self, expr: &Expr, prefix: &str, routes: &mut Vec<RouteObject>) {
+        if let Expr::MethodCall(_) = expr {
+            return self.walk_chain(expr, prefix, routes);
+        }
+
+        let router_chain =
+            path_of_call(expr).and_then(|name| self.router_fns.get(last_segment(&name)).copied());
+        if let Some(chain) = router_chain {
+            self.walk_chain(chain, prefix, routes);
+        }
+    }
+
+    // routes![index, create_task, ...] mounted at a base path
+    fn walk_mounted_routes(&self, expr: &Expr, base: &str, routes: &mut Vec<RouteObject>) {
+        let Expr::Macro(routes_macro) = expr else {
+            return;
+        };
+
+        let Ok(handlers) = routes_macro
+            .mac
+            .parse_body_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+        else {
+            return;
+        };
+
+        for handler in handlers {
+            let handler = handler
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+
+            if let Some((method, path)) = self.attribute_route(&handler) {
+                routes.push(self.build_route(method, &join_route(base, &path), &handler));
+            }
+        }
+    }
+
+    fn walk_service(&self, service: &Expr, prefix: &str, routes: &mut Vec<RouteObject>) {
+        // .service(handler) registers a #[get("/x")] style handler
+        if let Some(handler) = expr_path(service) {
+            if let Some((method, path)) = self.attribute_route(&handler) {
+                routes.push(self.build_route(method, &join_route(prefix, &path), &handler));
+            }
+            return;
+        }
+
+        let root = chain_root(service);
+        let (Some(root_path), Expr::Call(root_call)) = (path_of_call(root), root) else {
+            return;
+        };
+        let Some(base) = string_arg(root_call.args.first()) else {
+            return;
+        };
+        let base = join_route(prefix, &base);
+
+        match last_segment(&root_path) {
+            "scope" => self.walk_chain(service, &base, routes),
+            "resource" => self.walk_resource(service, &base, routes),
+            _ => {}
+        }
+    }
+
+    // web::resource("/x").route(web::get().to(a)).route(web::post().to(b))
+    fn walk_resource(&self, expr: &Expr, path: &str, routes: &mut Vec<RouteObject>) {
+        let Expr::MethodCall(call) = expr else {
+            return;
+        };
+
+        self.walk_resource(&call.receiver, path, routes);
+
+        if call.method == "route" && call.args.len() == 1 {
+            self.push_route(path, &call.args[0], routes);
+        }
+    }
+
+    // web::get().to(handler)
+    fn push_route(&self, path: &str, handler_expr: &Expr, routes: &mut Vec<RouteObject>) {
+        let Expr::MethodCall(ExprMethodCall {
+            receiver,
+            method,
+            args,
+            ..
+        }) = handler_expr
+        else {
+            return;
+        };
+
+        if method != "to" {
+            return;
+        }
+
+        let Some(http_method) =
+            path_of_call(receiver).and_then(|path| last_segment(&path).parse().ok())
+        else {
+            return;
+        };
+        let Some(handler) = args.first().and_then(expr_path) else {
+            return;
+        };
+
+        routes.push(self.build_route(http_method, path, &handler));
+    }
+
+    // #[get("/x")], or rocket's #[post("/x", data = "<body>")]
+    fn attribute_route(&self, handler: &str) -> Option<(HttpMethod, String)> {
+        let item = self.handler(handler)?;
+
+        let route_path = |input: ParseStream| -> syn::Result<LitStr> {
+            let path: LitStr = input.parse()?;
+            while !input.is_empty() {
+                input.parse::<Token![,]>()?;
+                if !input.is_empty() {
+                    input.parse::<Meta>()?;
+                }
+            }
+            Ok(path)
+        };
+
+        item.attrs.iter().find_map(|attr| {
+            let method = attr.path().get_ident()?.to_string().parse().ok()?;
+            let Meta::List(list) = &attr.meta else {
+                return None;
+            };
+            let path = route_path.parse2(list.tokens.clone()).ok()?.value();
+            Some((method, path))
+        })
+    }
+
+    fn build_route(&self, method: HttpMethod, path: &str, handler: &str) -> RouteObject {
+        let path = normalize_path_params(path);
+        let path = path.as_str();
+
+        let Some(item) = self.handler(handler) else {
+            return RouteObject::new(method, path, None, Some(TypeSchema::Unknown));
+        };
+
+        let request_body = item.sig.inputs.iter().find_map(|input| {
+            let FnArg::Typed(arg) = input else {
+                return None;
+            };
+            let (wrapper, inner) = wrapped_type(&arg.ty)?;
+            (wrapper == "Json").then(|| self.type_schema(inner, 0))
+        });
+
+        let response = match &item.sig.output {
+            ReturnType::Type(_, ty) => json_type(ty).map(|inner| self.type_schema(inner, 0)),
+            ReturnType::Default => None,
+        };
+
+        let response = response.or_else(|| {
+            let mut response_finder = JsonResponseFinder::default();
+            response_finder.visit_block(&item.block);
+            response_finder
+                .json_arg
+                .map(|expr| self.response_schema(expr, item))
+        });
+
+        RouteObject::new(method, path, request_body, response)
+    }
+
+    // Works out the type passed to HttpResponse::Ok().json(...) or Json(...) from the handler itself
+    fn response_schema(&self, expr: &Expr, handler: &ItemFn) -> TypeSchema {
+        match expr {
+            Expr::Reference(reference) => self.response_schema(&reference.expr, handler),
+            Expr::Paren(paren) => self.response_schema(&paren.expr, handler),
+            Expr::MethodCall(call)
+                if ["clone", "into_inner", "to_owned"]
+                    .contains(&call.method.to_string().as_str()) =>
+            {
+                self.response_schema(&call.receiver, handler)
+            }
+            Expr::Call(call)
+                if expr_path(&call.func).is_some_and(|path| last_segment(&path) == "Json") =>
+            {
+                call.args.first().map_or(TypeSchema::Unknown, |arg| {
+                    self.response_schema(arg, handler)
+                })
+            }
+            Expr::Struct(literal) => {
+                let name = literal
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string());
+                name.map_or(TypeSchema::Unknown, |name| self.struct_schema(&name, 0))
+            }
+            Expr::Array(_) => TypeSchema::Array(Box::new(TypeSchema::Unknown)),
+            Expr::Lit(lit) => match lit.lit {
+                Lit::Str(_) => TypeSchema::String,
+                Lit::Int(_) | Lit::Float(_) => TypeSchema::Number,
+                Lit::Bool(_) => TypeSchema::Bool,
+                _ => TypeSchema::Unknown,
+            },
+            Expr::Path(_) => {
+                let name = expr_path(expr).unwrap_or_default();
+                self.variable_schema(&name, handler)
+            }
+            _ => TypeSchema::Unknown,
+        }
+    }
+
+    // A variable is typed by a handler argument, a typed let binding, or a struct named after it
+    fn variable_schema(&self, name: &str, handler: &ItemFn) -> TypeSchema {
+        for input in &handler.sig.inputs {
+            if let FnArg::Typed(arg) = input {
+                if pat_binds(&arg.pat, name) {
+                    return match wrapped_type(&arg.ty) {
+                        Some((_, inner)) => self.type_schema(inner, 0),
+                        None => self.type_schema(&arg.ty, 0),
+                    };
+                }
+            }
+        }
+
+        let mut let_finder = TypedLetFinder { name, ty: None };
+        let_finder.visit_block(&handler.block);
+        if let Some(ty) = let_finder.ty {
+            return self.type_schema(ty, 0);
+        }
+
+        let struct_name = |name: &str| -> Option<String> {
+            self.structs
+                .keys()
+                .find(|candidate| candidate.eq_ignore_ascii_case(name))
+                .cloned()
+        };
+
+        if let Some(found) = struct_name(name) {
+            return self.struct_schema(&found, 0);
+        }
+
+        if let Some(found) = name.strip_suffix('s').and_then(struct_name) {
+            return TypeSchema::Array(Box::new(self.struct_schema(&found, 0)));
+        }
+
+        TypeSchema::Unknown
+    }
+
+    fn struct_schema(&self, name: &str, depth: u8) -> TypeSchema {
+        let Some(item) = self.structs.get(name) else {
+            return TypeSchema::Unknown;
+        };
+
+        let Fields::Named(fields) = &item.fields else {
+            return TypeSchema::Unknown;
+        };
+
+        TypeSchema::Object(
+            fields
+                .named
+                .iter()
+                .filter_map(|field| {
+                    let name = field.ident.as_ref()?.to_string();
+                    Some((name, self.type_schema(&field.ty, depth + 1)))
+                })
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    fn type_schema(&self, ty: &Type, depth: u8) -> TypeSchema {
+        if depth > MAX_TYPE_DEPTH {
+            return TypeSchema::Unknown;
+        }
+
+        match ty {
+            Type::Reference(reference) => self.type_schema(&reference.elem, depth),
+            Type::Slice(slice) => {
+                TypeSchema::Array(Box::new(self.type_schema(&slice.elem, depth + 1)))
+            }
+            Type::Array(array) => {
+                TypeSchema::Array(Box::new(self.type_schema(&array.elem, depth + 1)))
+            }
+            Type::Path(path) => {
+                let Some(segment) = path.path.segments.last() else {
+                    return TypeSchema::Unknown;
+                };
+                let name = segment.ident.to_string();
+                let first_generic = generic_types(&segment.arguments).into_iter().next();
+
+                match name.as_str() {
+                    "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => TypeSchema::Array(Box::new(
+                        first_generic.map_or(TypeSchema::Unknown, |inner| {
+                            self.type_schema(inner, depth + 1)
+                        }),
+                    )),
+                    "Option" | "Box" | "Json" | "Data" => first_generic
+                        .map_or(TypeSchema::Unknown, |inner| self.type_schema(inner, depth)),
+                    _ if self.structs.contains_key(&name) => self.struct_schema(&name, depth),
+                    _ => TypeSchema::from_llm_value(&serde_json::Value::String(name))
+                        .unwrap_or(TypeSchema::Unknown),
+                }
+            }
+            _ => TypeSchema::Unknown,
+        }
+    }
+}
+
+fn generic_types(arguments: &PathArguments) -> Vec<&Type> {
+    match arguments {
+        PathArguments::AngleBracketed(generics) => generics
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+// The T of a Json<T> anywhere in a return type, such as Option<Json<T>> or (StatusCode, Json<T>)
+fn json_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(path) => {
+            let segment = path.path.segments.last()?;
+            let generics = generic_types(&segment.arguments);
+
+            if segment.ident == "Json" {
+                return generics.into_iter().next();
+            }
+
+            generics.into_iter().find_map(json_type)
+        }
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(json_type),
+        Type::Reference(reference) => json_type(&reference.elem),
+        _ => None,
+    }
+}
+
+// Splits web::Json<Task> into ("Json", Task)
+fn wrapped_type(ty: &Type) -> Option<(String, &Type)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let inner = generic_types(&segment.arguments).into_iter().next()?;
+
+    Some((segment.ident.to_string(), inner))
+}
+
+#[derive(Default)]
+struct JsonResponseFinder<'ast> {
+    json_arg: Option<&'ast Expr>,
+}
+
+impl<'ast> Visit<'ast> for JsonResponseFinder<'ast> {
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        let is_success_response = path_of_call(chain_root(&call.receiver)).is_some_and(|path| {
+            path.starts_with("HttpResponse::") && ["Ok", "Created"].contains(&last_segment(&path))
+        });
+
+        if self.json_arg.is_none() && call.method == "json" && is_success_response {
+            self.json_arg = call.args.first();
+        }
+
+        visit::visit_expr_method_call(self, call);
+    }
+
+    // axum and rocket respond with Json(value)
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        let is_json = expr_path(&call.func).is_some_and(|path| last_segment(&path) == "Json");
+
+        if self.json_arg.is_none() && is_json {
+            self.json_arg = call.args.first();
+        }
+
+        visit::visit_expr_call(self, call);
+    }
+}
+
+struct TypedLetFinder<'a, 'ast> {
+    name: &'a str,
+    ty: Option<&'ast Type>,
+}
+
+impl<'ast> Visit<'ast> for TypedLetFinder<'_, 'ast> {
+    fn visit_pat_type(&mut self, pat: &'ast syn::PatType) {
+        if matches!(&*pat.pat, Pat::Ident(ident) if ident.ident == self.name) {
+            self.ty = Some(&pat.ty);
+        }
+
+        visit::visit_pat_type(self, pat);
+    }
+}
+
+// "/task/{id}", "/task/{task_id}" and "/task/<id>" are the same route
+pub fn route_key(route: &RouteObject) -> (HttpMethod, String) {
+    let path = normalize_path_params(&route.route)
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with('{') {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    (route.method, path)
+}
+
+// Only compares shapes both sides actually know, so "unknown" never counts as a disagreement
+fn schemas_disagree(llm: Option<&TypeSchema>, extracted: Option<&TypeSchema>) -> bool {
+    match (llm, extracted) {
+        (Some(TypeSchema::Unknown), _) | (_, Some(TypeSchema::Unknown)) => false,
+        (Some(TypeSchema::Object(a)), Some(TypeSchema::Object(b))) => {
+            a.keys().ne(b.keys())
+                || a.iter()
+                    .any(|(key, value)| schemas_disagree(Some(value), b.get(key)))
+        }
+        (Some(TypeSchema::Array(a)), Some(TypeSchema::Array(b))) => {
+            schemas_disagree(Some(a), Some(b))
+        }
+        (llm, extracted) => llm != extracted,
+    }
+}
+
+// Lists where the LLM endpoint extraction and the static extraction do not agree
+pub fn compare_route_extractions(
+    llm_routes: &[RouteObject],
+    static_routes: &[RouteObject],
+) -> Vec<String> {
+    let llm: BTreeMap<_, _> = llm_routes
+        .iter()
+        .map(|route| (route_key(route), route))
+        .collect();
+    let extracted: BTreeMap<_, _> = static_routes
+        .iter()
+        .filter(|route| route.route != "/")
+        .map(|route| (route_key(route), route))
+        .collect();
+
+    let mut disagreements = vec![];
+
+    for (key, route) in &extracted {
+        match llm.get(key) {
+            None => disagreements.push(format!(
+                "{} {} is in the code but missing from the LLM extraction",
+                route.method, route.route
+            )),
+            Some(llm_route) => {
+                if schemas_disagree(llm_route.request_body.as_ref(), route.request_body.as_ref()) {
+                    disagreements.push(format!(
+                        "{} {} request body: LLM says {:?}, code says {:?}",
+                        route.method, route.route, llm_route.request_body, route.request_body
+                    ));
+                }
+                if schemas_disagree(llm_route.response.as_ref(), route.response.as_ref()) {
+                    disagreements.push(format!(
+                        "{} {} response: LLM says {:?}, code says {:?}",
+                        route.method, route.route, llm_route.response, route.response
+                    ));
+                }
+            }
+        }
+    }
+
+    for (key, route) in &llm {
+        if !extracted.contains_key(key) {
+            disagreements.push(format!(
+                "{} {} is in the LLM extraction but not registered in the code",
+                route.method, route.route
+            ));
+        }
+    }
+
+    disagreements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIX_CODE: &str = r#"
+        #[derive(Serialize, Deserialize)]
+        struct Task { id: u64, name: String, completed: bool }
+
+        async fn create_task(app_state: web::Data<AppState>, task: web::Json<Task>) -> impl Responder {
+            HttpResponse::Ok().finish()
+        }
+
+        async fn read_task(app_state: web::Data<AppState>, id: web::Path<u64>) -> impl Responder {
+            match db.get(&id.into_inner()) {
+                Some(task) => HttpResponse::Ok().json(task),
+                None => HttpResponse::NotFound().finish(),
+            }
+        }
+
+        async fn read_all_tasks(app_state: web::Data<AppState>) -> impl Responder {
+            let tasks = db.get_all();
+            HttpResponse::Ok().json(tasks)
+        }
+
+        #[get("/health")]
+        async fn health() -> impl Responder {
+            HttpResponse::Ok().json("ok")
+        }
+
+        #[actix_web::main]
+        async fn main() -> std::io::Result<()> {
+            HttpServer::new(move || {
+                App::new()
+                    .app_data(data.clone())
+                    .route("/task", web::post().to(create_task))
+                    .service(web::resource("/task/{id}").route(web::get().to(read_task)))
+                    .service(web::scope("/api").route("/tasks", web::get().to(read_all_tasks)))
+                    .service(health)
+            })
+            .bind("127.0.0.1:8080")?
+            .run()
+            .await
+        }
+    "#;
+
+    const AXUM_CODE: &str = r#"
+        #[derive(Serialize, Deserialize)]
+        struct Task { id: u64, name: String, completed: bool }
+
+        async fn create_task(State(state): State<SharedState>, Json(task): Json<Task>) -> StatusCode {
+            StatusCode::CREATED
+        }
+
+        async fn read_task(Path(id): Path<u64>) -> Result<Json<Task>, StatusCode> {
+            state.get(&id).cloned().map(Json).ok_or(StatusCode::NOT_FOUND)
+        }
+
+        async fn read_all_tasks(State(state): State<SharedState>) -> impl IntoResponse {
+            let tasks: Vec<Task> = state.get_all();
+            Json(tasks)
+        }
+
+        async fn delete_task(Path(id): Path<u64>) -> StatusCode {
+            StatusCode::OK
+        }
+
+        fn task_routes() -> Router<SharedState> {
+            Router::new()
+                .route("/", post(create_task).get(read_all_tasks))
+                .route("/:id", axum::routing::get(read_task).delete(delete_task))
+        }
+
+        fn app(state: SharedState) -> Router {
+            Router::new().nest("/task", task_routes()).with_state(state)
+        }
+    "#;
+
+    const ROCKET_CODE: &str = r#"
+        #[derive(Serialize, Deserialize)]
+        struct Task { id: u64, name: String, completed: bool }
+
+        #[post("/task", data = "<task>")]
+        fn create_task(db: &State<Database>, task: Json<Task>) -> Status {
+            Status::Created
+        }
+
+        #[get("/task/<id>")]
+        fn read_task(db: &State<Database>, id: u64) -> Option<Json<Task>> {
+            db.get(&id).cloned().map(Json)
+        }
+
+        #[get("/tasks")]
+        fn read_all_tasks(db: &State<Database>) -> Json<Vec<Task>> {
+            Json(db.get_all())
+        }
+
+        #[options("/<_..>")]
+        fn preflight() {}
+
+        #[launch]
+        fn rocket() -> _ {
+            rocket::build()
+                .mount("/", routes![create_task, read_task, preflight])
+                .mount("/api", routes![read_all_tasks])
+        }
+    "#;
+
+    const SPLIT_AXUM_FILES: [(&str, &str); 3] = [
+        (
+            "src/main.rs",
+            r#"
+            mod routes;
+
+            fn app(state: SharedState) -> Router {
+                Router::new()
+                    .nest("/task", routes::tasks::router())
+                    .route("/user", post(routes::users::create))
+                    .with_state(state)
+            }
+            "#,
+        ),
+        (
+            "src/routes/tasks.rs",
+            r#"
+            #[derive(Serialize, Deserialize)]
+            pub struct Task { id: u64, name: String, completed: bool }
+
+            pub async fn create(Json(task): Json<Task>) -> Json<Task> {
+                Json(task)
+            }
+
+            pub fn router() -> Router<SharedState> {
+                Router::new().route("/", post(create))
+            }
+            "#,
+        ),
+        (
+            "src/routes/users.rs",
+            r#"
+            pub async fn create(Json(name): Json<String>) -> StatusCode {
+                StatusCode::CREATED
+            }
+            "#,
+        ),
+    ];
+
+    fn extract_routes_from_code(
+        code: &str,
+        framework: WebFramework,
+    ) -> Result<Vec<RouteObject>, syn::Error> {
+        extract_routes_from_sources([("src/main.rs", code)], framework)
+    }
+
+    fn task_schema() -> TypeSchema {
+        TypeSchema::Object(BTreeMap::from([
+            ("completed".to_string(), TypeSchema::Bool),
+            ("id".to_string(), TypeSchema::Number),
+            ("name".to_string(), TypeSchema::String),
+        ]))
+    }
+
+    #[test]
+    fn tests_extract_routes_from_code() {
+        let routes = extract_routes_from_code(ACTIX_CODE, WebFramework::Actix)
+            .expect("Failed to parse code");
+
+        assert_eq!(
+            routes,
+            vec![
+                RouteObject::new(HttpMethod::Post, "/task", Some(task_schema()), None),
+                RouteObject::new(HttpMethod::Get, "/task/{id}", None, Some(task_schema())),
+                RouteObject::new(
+                    HttpMethod::Get,
+                    "/api/tasks",
+                    None,
+                    Some(TypeSchema::Array(Box::new(task_schema())))
+                ),
+                RouteObject::new(HttpMethod::Get, "/health", None, Some(TypeSchema::String)),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_extract_routes_from_axum_code() {
+        let routes =
+            extract_routes_from_code(AXUM_CODE, WebFramework::Axum).expect("Failed to parse code");
+
+        assert_eq!(
+            routes,
+            vec![
+                RouteObject::new(HttpMethod::Post, "/task", Some(task_schema()), None),
+                RouteObject::new(
+                    HttpMethod::Get,
+                    "/task",
+                    None,
+                    Some(TypeSchema::Array(Box::new(task_schema())))
+                ),
+                RouteObject::new(HttpMethod::Get, "/task/{id}", None, Some(task_schema())),
+                RouteObject::new(HttpMethod::Delete, "/task/{id}", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_extract_routes_from_rocket_code() {
+        let routes = extract_routes_from_code(ROCKET_CODE, WebFramework::Rocket)
+            .expect("Failed to parse code");
+
+        assert_eq!(
+            routes,
+            vec![
+                RouteObject::new(HttpMethod::Post, "/task", Some(task_schema()), None),
+                RouteObject::new(HttpMethod::Get, "/task/{id}", None, Some(task_schema())),
+                RouteObject::new(
+                    HttpMethod::Get,
+                    "/api/tasks",
+                    None,
+                    Some(TypeSchema::Array(Box::new(task_schema())))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_extract_routes_from_sources() {
+        let routes = extract_routes_from_sources(SPLIT_AXUM_FILES, WebFramework::Axum)
+            .expect("Failed to parse code");
+
+        assert_eq!(
+            routes,
+            vec![
+                RouteObject::new(
+                    HttpMethod::Post,
+                    "/task",
+                    Some(task_schema()),
+                    Some(task_schema())
+                ),
+                RouteObject::new(HttpMethod::Post, "/user", Some(TypeSchema::String), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn tests_compare_route_extractions() {
+        let static_routes = extract_routes_from_code(ACTIX_CODE, WebFramework::Actix)
+            .expect("Failed to parse code");
+        let llm_routes = vec![
+            RouteObject::new(HttpMethod::Post, "/task", Some(task_schema()), None),
+            RouteObject::new(
+                HttpMethod::Get,
+                "/task/{task_id}",
+                None,
+                Some(TypeSchema::Unknown),
+            ),
+            RouteObject::new(
+                HttpMethod::Get,
+                "/api/tasks",
+                None,
+                Some(TypeSchema::String),
+            ),
+            RouteObject::new(HttpMethod::Delete, "/task/{id}", None, None),
+        ];
+
+        let disagreements = compare_route_extractions(&llm_routes, &static_routes);
+
+        assert_eq!(disagreements.len(), 3);
+        assert!(disagreements[0].contains("get /api/tasks response"));
+        assert!(disagreements[1].contains("get /health is in the code"));
+        assert!(disagreements[2].contains("delete /task/{id} is in the LLM extraction"));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::models::{
+    agents::agent_traits::ProjectScope,
+    general::{
+        framework::WebFramework,
+        template::{CodeTemplate, TemplateKind},
+    },
+};
+
+const BUILTIN_TEMPLATES_PATH: &str =
+    "/Users/gbubemismith/Documents/Rust/rustptty/assets/templates/";
+
+// Looks templates up in user supplied directories before the built in ones. Every directory
+// uses the same layout: <framework>/Cargo.toml and <framework>/<kind>/main.rs
+#[derive(Debug, Clone)]
+pub struct TemplateRegistry {
+    dirs: Vec<PathBuf>,
+}
+
+impl TemplateRegistry {
+    pub fn new(user_dirs: &[PathBuf]) -> Self {
+        let mut dirs = user_dirs.to_vec();
+        dirs.push(PathBuf::from(BUILTIN_TEMPLATES_PATH));
+
+        Self::from_dirs(dirs)
+    }
+
+    pub fn from_dirs(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    // A user directory may override just the code, just the Cargo.toml, or both
+    pub fn find(&self, framework: WebFramework, kind: TemplateKind) -> Option<CodeTemplate> {
+        let code_path = self.first_existing(|dir| {
+            dir.join(framework.as_str())
+                .join(kind.as_str())
+                .join("main.rs")
+        })?;
+        let manifest_path =
+            self.first_existing(|dir| dir.join(framework.as_str()).join("Cargo.toml"))?;
+
+        Some(CodeTemplate {
+            kind,
+            framework,
+            code_path,
+            manifest_path,
+        })
+    }
+
+    pub fn select(
+        &self,
+        framework: WebFramework,
+        scope: Option<&ProjectScope>,
+    ) -> Result<CodeTemplate, io::Error> {
+        let kind = TemplateKind::from_scope(scope);
+
+        self.find(framework, kind).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No {} template for {} in {:?}", kind, framework, self.dirs),
+            )
+        })
+    }
+
+    fn first_existing(&self, path_in: impl Fn(&Path) -> PathBuf) -> Option<PathBuf> {
+        self.dirs
+            .iter()
+            .map(|dir| path_in(dir))
+            .find(|path| path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn repo_templates() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/templates")
+    }
+
+    #[test]
+    fn tests_builtin_templates_cover_every_framework() {
+        let registry = TemplateRegistry::from_dirs(vec![repo_templates()]);
+
+        for framework in [
+            WebFramework::Actix,
+            WebFramework::Axum,
+            WebFramework::Rocket,
+        ] {
+            for kind in [
+                TemplateKind::Crud,
+                TemplateKind::CrudAuth,
+                TemplateKind::ExternalApiProxy,
+                TemplateKind::Minimal,
+            ] {
+                assert!(
+                    registry.find(framework, kind).is_some(),
+                    "Missing {} template for {}",
+                    kind,
+                    framework
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tests_user_templates_take_precedence() {
+        let user_dir = std::env::temp_dir().join("rustptty_user_templates");
+        let crud_dir = user_dir.join("axum").join("crud");
+        fs::create_dir_all(&crud_dir).expect("Failed to create user template dir");
+        fs::write(crud_dir.join("main.rs"), "fn main() {}").expect("Failed to write template");
+
+        let registry = TemplateRegistry::from_dirs(vec![user_dir.clone(), repo_templates()]);
+        let scope = ProjectScope {
+            is_crud_required: true,
+            is_user_login_and_logout: false,
+            is_external_urls_required: false,
+        };
+
+        let crud = registry
+            .select(WebFramework::Axum, Some(&scope))
+            .expect("Failed to select template");
+        assert_eq!(crud.kind, TemplateKind::Crud);
+        assert_eq!(crud.code_path, crud_dir.join("main.rs"));
+        assert_eq!(crud.manifest_path, repo_templates().join("axum/Cargo.toml"));
+
+        let minimal = registry
+            .select(WebFramework::Axum, None)
+            .expect("Failed to select template");
+        assert_eq!(
+            minimal.code_path,
+            repo_templates().join("axum/minimal/main.rs")
+        );
+
+        let missing = TemplateRegistry::from_dirs(vec![user_dir.clone()]);
+        assert!(missing.select(WebFramework::Axum, Some(&scope)).is_err());
+
+        fs::remove_dir_all(user_dir).expect("Failed to clean up user template dir");
+    }
+}
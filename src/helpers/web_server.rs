@@ -0,0 +1,177 @@
+use std::{
+    fs, io,
+    path::Path,
+    process::{Child, Command, Output, Stdio},
+    time::Duration,
+};
+
+use reqwest::Client;
+
+use crate::models::general::persistence::PersistenceBackend;
+
+const WEB_SERVER_PROJECT_PATH: &str = "/Users/gbubemismith/Documents/Rust/rustptty/web_template/";
+const WEB_SERVER_DATABASE_PATH: &str =
+    "/Users/gbubemismith/Documents/Rust/rustptty/web_template/database.json";
+pub const WEB_SERVER_URL: &str = "http://127.0.0.1:8080";
+
+fn cargo_command(project_path: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new("cargo");
+    command
+        .args(args)
+        .current_dir(project_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    command
+}
+
+fn run_cargo(args: &[&str]) -> Result<Output, io::Error> {
+    cargo_command(Path::new(WEB_SERVER_PROJECT_PATH), args).output()
+}
+
+// The SQL backends connect to DATABASE_URL, in the server and in its tests
+fn set_database_url(command: &mut Command, persistence: PersistenceBackend) -> &mut Command {
+    if let Some(database_url) = persistence.database_url() {
+        command.env("DATABASE_URL", database_url);
+    }
+
+    command
+}
+
+pub fn build_web_server() -> Result<Output, io::Error> {
+    run_cargo(&["build"])
+}
+
+pub fn test_web_server(persistence: PersistenceBackend) -> Result<Output, io::Error> {
+    let mut command = cargo_command(Path::new(WEB_SERVER_PROJECT_PATH), &["test"]);
+    set_database_url(&mut command, persistence).output()
+}
+
+// Crates generated inside the project, such as the API client, live in their own folder
+pub fn build_nested_crate(relative_path: &str) -> Result<Output, io::Error> {
+    let project_path = Path::new(WEB_SERVER_PROJECT_PATH).join(relative_path);
+    cargo_command(&project_path, &["build", "--all-targets"]).output()
+}
+
+// Examples reach the running server through API_BASE_URL
+pub fn run_nested_crate_example(relative_path: &str, example: &str) -> Result<Output, io::Error> {
+    let project_path = Path::new(WEB_SERVER_PROJECT_PATH).join(relative_path);
+    cargo_command(&project_path, &["run", "--example", example])
+        .env("API_BASE_URL", WEB_SERVER_URL)
+        .output()
+}
+
+// Compiler errors live on stderr, failing test assertions on stdout
+pub fn command_failure_report(output: &Output) -> String {
+    format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+// Runs the generated server from its own folder so database.json and static/ resolve there
+pub async fn start_web_server(
+    client: &Client,
+    persistence: PersistenceBackend,
+) -> Result<Child, Box<dyn std::error::Error>> {
+    let mut command = Command::new("cargo");
+    command
+        .arg("run")
+        .current_dir(WEB_SERVER_PROJECT_PATH)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let mut server = set_database_url(&mut command, persistence).spawn()?;
+
+    for _ in 0..30 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        if client.get(WEB_SERVER_URL).send().await.is_ok() {
+            return Ok(server);
+        }
+
+        if server.try_wait()?.is_some() {
+            return Err("Web server exited before accepting requests".into());
+        }
+    }
+
+    server.kill()?;
+    Err("Web server did not start in time".into())
+}
+
+pub fn stop_web_server(mut server: Child) -> Result<(), io::Error> {
+    server.kill()?;
+    server.wait()?;
+
+    Ok(())
+}
+
+// Writes a file relative to the generated project root, such as a Dockerfile or Makefile
+pub fn save_web_server_file(file_name: &str, content: &str) -> Result<(), io::Error> {
+    let path = Path::new(WEB_SERVER_PROJECT_PATH).join(file_name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, content)
+}
+
+// Everything the generated server stored, as text to search. A SQLite file keeps strings as
+// they were written, Postgres is dumped with pg_dump
+pub fn read_web_server_database(persistence: PersistenceBackend) -> Result<String, io::Error> {
+    let database_url = persistence.database_url().unwrap_or_default();
+
+    match persistence {
+        PersistenceBackend::JsonFile => fs::read_to_string(WEB_SERVER_DATABASE_PATH),
+        PersistenceBackend::Sqlite => {
+            let path = sqlite_database_path(&database_url).ok_or_else(|| {
+                io::Error::other(format!("{} is not a SQLite file", database_url))
+            })?;
+            let content = fs::read(Path::new(WEB_SERVER_PROJECT_PATH).join(path))?;
+
+            Ok(String::from_utf8_lossy(&content).into_owned())
+        }
+        PersistenceBackend::Postgres => {
+            let output = Command::new("pg_dump")
+                .args(["--data-only", "--dbname", &database_url])
+                .output()?;
+            if !output.status.success() {
+                return Err(io::Error::other(command_failure_report(&output)));
+            }
+
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+    }
+}
+
+// sqlite://database.db?mode=rwc -> database.db, relative to the project
+fn sqlite_database_path(database_url: &str) -> Option<&str> {
+    let path = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))?;
+    let path = path.split('?').next().unwrap_or_default();
+
+    (!path.is_empty() && path != ":memory:").then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_sqlite_database_path() {
+        assert_eq!(
+            sqlite_database_path("sqlite://database.db?mode=rwc"),
+            Some("database.db")
+        );
+        assert_eq!(
+            sqlite_database_path("sqlite:/app/data/database.db"),
+            Some("/app/data/database.db")
+        );
+        assert_eq!(sqlite_database_path("sqlite::memory:"), None);
+        assert_eq!(
+            sqlite_database_path("postgres://localhost/web_template"),
+            None
+        );
+    }
+}
@@ -8,13 +8,143 @@ macro_rules! get_function_string {
 #[macro_use]
 mod ai_functions;
 mod apis;
+mod exporters;
 mod helpers;
 mod models;
 
-use helpers::cli;
+use helpers::{
+    cli::{self, CliCommand},
+    history,
+};
+use models::agents_manager::{
+    agent_registry::AgentRegistry,
+    managing_agent::ManagingAgent,
+    pipeline::{check_pipeline, PipelineFile},
+};
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match cli::parse_command(&args) {
+        Ok(CliCommand::Run(options)) => options,
+        Ok(CliCommand::Rollback(iteration)) => {
+            if let Err(err) = history::rollback_backend(iteration) {
+                eprintln!("Rollback failed: {}", err);
+                std::process::exit(1);
+            }
+            println!("Restored the backend code of iteration {}", iteration);
+            return;
+        }
+        Ok(CliCommand::Amend(request)) => {
+            let request =
+                request.unwrap_or_else(|| cli::get_user_response("What should we change?"));
+
+            let mut managing_agent = match ManagingAgent::amend(request).await {
+                Ok(managing_agent) => managing_agent,
+                Err(err) => {
+                    eprintln!("Amend failed: {}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            managing_agent.execute_agents().await;
+            return;
+        }
+        Ok(CliCommand::Import(crate_path)) => {
+            match ManagingAgent::import(&crate_path).await {
+                Ok(managing_agent) => {
+                    let factsheet = managing_agent.factsheet();
+                    println!(
+                        "Imported {} as a {} project with {} endpoints: {}",
+                        crate_path.display(),
+                        factsheet.web_framework,
+                        factsheet.api_endpoint_schema.as_ref().map_or(0, Vec::len),
+                        factsheet.project_description
+                    );
+                    println!("The OpenAPI spec is saved, run `amend <request>` or `tests` next");
+                }
+                Err(err) => {
+                    eprintln!("Import failed: {}", err);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Ok(CliCommand::Tests) => {
+            let mut managing_agent = match ManagingAgent::testing() {
+                Ok(managing_agent) => managing_agent,
+                Err(err) => {
+                    eprintln!("Testing failed: {}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            managing_agent.execute_agents().await;
+            if let Err(err) = managing_agent.commit_iteration("Test writer: integration tests") {
+                eprintln!("Failed to record the tests in git: {}", err);
+            }
+            return;
+        }
+        Ok(CliCommand::Agents(pipeline)) => {
+            let loaded = pipeline
+                .as_deref()
+                .map(PipelineFile::load)
+                .transpose()
+                .and_then(|pipeline| {
+                    let registry = match &pipeline {
+                        Some(pipeline) => pipeline.registry()?,
+                        None => AgentRegistry::with_builtin_agents(),
+                    };
+                    Ok((registry, pipeline))
+                });
+            let (registry, pipeline) = match loaded {
+                Ok(loaded) => loaded,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            for registration in registry.registrations() {
+                let is_run = pipeline
+                    .as_ref()
+                    .is_none_or(|pipeline| pipeline.runs_agent(&registration.name));
+                println!(
+                    "{:>4} {}{}: {}",
+                    registration.position,
+                    registration.name,
+                    if is_run { "" } else { " (not run)" },
+                    registration.description
+                );
+            }
+            return;
+        }
+        Ok(CliCommand::PipelineCheck(path)) => {
+            let report = check_pipeline(&path);
+            for error in &report.errors {
+                eprintln!("error: {}", error);
+            }
+            for warning in &report.warnings {
+                eprintln!("warning: {}", warning);
+            }
+
+            if !report.errors.is_empty() {
+                std::process::exit(1);
+            }
+            println!("{} is valid", path.display());
+            return;
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
 
-fn main() {
     let user_req = cli::get_user_response("What are we building today?");
 
-    dbg!(user_req);
+    let mut managing_agent = ManagingAgent::new(user_req, options)
+        .await
+        .expect("Error creating managing agent");
+
+    managing_agent.execute_project().await;
 }
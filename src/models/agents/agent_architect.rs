@@ -15,7 +15,7 @@ use crate::{
     },
 };
 
-use super::agent_traits::{FactSheet, ProjectScope, SpecialFunctions};
+use super::agent_traits::{FactField, FactSheet, ProjectScope, ReplanTrigger, SpecialFunctions};
 
 #[derive(Debug)]
 pub struct AgentSolutionArchitect {
@@ -76,6 +76,21 @@ impl SpecialFunctions for AgentSolutionArchitect {
         &self.attributes
     }
 
+    fn reads(&self) -> &[FactField] {
+        &[FactField::ProjectDescription]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[FactField::ProjectScope, FactField::ExternalUrls]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[
+            get_function_string!(print_project_scope),
+            get_function_string!(print_site_urls),
+        ]
+    }
+
     async fn execute(
         &mut self,
         factsheet: &mut FactSheet,
@@ -101,6 +116,7 @@ impl SpecialFunctions for AgentSolutionArchitect {
                     let client = Client::builder().timeout(Duration::from_secs(5)).build()?;
 
                     let urls = factsheet.external_urls.as_ref().expect("No urls found");
+                    let urls_count = urls.len();
 
                     for url in urls {
                         let endpount_str = format!("Testing url endpoint: {}", url);
@@ -116,7 +132,10 @@ impl SpecialFunctions for AgentSolutionArchitect {
                                 }
                             }
 
-                            Err(e) => println!("Error checking {}: {}", url, e),
+                            Err(e) => {
+                                println!("Error checking {}: {}", url, e);
+                                exclude_urls.push(url.clone());
+                            }
                         }
                     }
 
@@ -133,6 +152,11 @@ impl SpecialFunctions for AgentSolutionArchitect {
                         factsheet.external_urls = Some(new_urls);
                     }
 
+                    // The manager can look for other urls or drop them from the scope
+                    if !exclude_urls.is_empty() && exclude_urls.len() == urls_count {
+                        return Err(Box::new(ReplanTrigger::ExternalUrlsDead(exclude_urls)));
+                    }
+
                     self.attributes.state = AgentState::Finished;
                 }
 
@@ -147,6 +171,9 @@ impl SpecialFunctions for AgentSolutionArchitect {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::general::{
+        edit_mode::EditMode, framework::WebFramework, persistence::PersistenceBackend,
+    };
 
     #[tokio::test]
     async fn tests_solution_architect() {
@@ -156,8 +183,16 @@ mod tests {
             project_description: "Build a smaple full-stack website with login and logout that shows latest stock prices".to_string(),
             project_scope: None,
             external_urls: None,
-            backend_code: None,
-            api_endpoint_schema: None
+            backend_files: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
         };
 
         agent
@@ -1,23 +1,71 @@
 use std::io::Read;
 
+use async_trait::async_trait;
+use reqwest::Client;
+
 use crate::{
     ai_functions::aifunc_backend::{
-        print_backend_webserver_code, print_fixed_code, print_improved_webserver_code,
-        print_rest_api_endpoints,
+        print_backend_webserver_code, print_fixed_code, print_fixed_code_patches,
+        print_improved_webserver_code, print_improved_webserver_patches, print_rest_api_endpoints,
+    },
+    exporters::{
+        clients::{save_api_clients, RUST_CLIENT_PATH, RUST_CLIENT_SMOKE_EXAMPLE},
+        collections::save_request_collections,
+        migrations::save_migrations,
+        openapi::save_openapi_spec,
+    },
+    helpers::{
+        cli::PrintCommand,
+        general::{
+            extract_code_from_code_block, perfom_ai_call, perfom_ai_call_decoded,
+            read_code_template_content, read_framework_manifest, save_api_endpoints, save_artifact,
+            save_backend_files,
+        },
+        history::commit_backend_iteration,
+        patcher::{apply_patches, parse_patches},
+        route_extractor::{
+            compare_route_extractions, extract_routes_from_sources, retain_api_routes, route_key,
+        },
+        templates::TemplateRegistry,
+        web_server::{
+            build_nested_crate, build_web_server, command_failure_report, run_nested_crate_example,
+            start_web_server, stop_web_server,
+        },
     },
-    helpers::general::{
-        perfom_ai_call, read_code_template_content, read_exec_main_contents, save_backend_code,
+    models::{
+        agent_basic::{
+            basic_agent::{AgentState, BasicAgent},
+            basic_trait::BasicTraits,
+        },
+        general::{
+            backend_files::{BackendFiles, MAIN_FILE, MANIFEST_FILE, TESTS_FILE},
+            edit_mode::EditMode,
+            persistence::PersistenceBackend,
+        },
     },
-    models::agent_basic::basic_agent::{AgentState, BasicAgent},
 };
 
-use super::agent_traits::FactSheet;
+use super::{
+    agent_code_reviewer::{AgentCodeReviewer, ReviewFinding},
+    agent_data_model::find_data_model_mismatches,
+    agent_test_writer::AgentTestWriter,
+    agent_traits::{FactField, FactSheet, ReplanTrigger, RouteObject, SpecialFunctions},
+};
+
+const MAX_BUG_FIX_ATTEMPTS: u8 = 3;
+const MAX_REVIEW_ROUNDS: u8 = 2;
+const ROUTE_DISAGREEMENTS_ARTIFACT: &str = "route_extraction_disagreements.json";
 
 #[derive(Debug)]
 pub struct AgentBackendDeveloper {
     attributes: BasicAgent,
     bug_errors: Option<String>,
     bug_count: u8,
+    review_findings: Vec<ReviewFinding>,
+    review_rounds: u8,
+    last_ai_function: &'static str,
+    amend_goal: Option<String>,
+    existing_routes: Vec<RouteObject>,
 }
 
 impl AgentBackendDeveloper {
@@ -33,21 +81,76 @@ impl AgentBackendDeveloper {
             attributes,
             bug_errors: None,
             bug_count: 0,
+            review_findings: vec![],
+            review_rounds: 0,
+            last_ai_function: get_function_string!(print_backend_webserver_code),
+            amend_goal: None,
+            existing_routes: vec![],
         }
     }
 
+    // Starts from the code a previous run left behind and changes it towards amend_goal,
+    // keeping every route the FactSheet already lists
+    pub fn amending(amend_goal: String, factsheet: &FactSheet) -> Self {
+        let mut agent = Self::new();
+        agent.attributes.update_state(AgentState::Working);
+        agent.amend_goal = Some(amend_goal);
+        agent.existing_routes = factsheet.api_endpoint_schema.clone().unwrap_or_default();
+
+        agent
+    }
+
     async fn call_initial_backend_code(&mut self, factsheet: &mut FactSheet) {
+        // A re-plan may have picked a template other than the one the scope points to
+        let template = match factsheet
+            .code_template
+            .clone()
+            .filter(|template| template.framework == factsheet.web_framework)
+        {
+            Some(template) => template,
+            None => TemplateRegistry::new(&factsheet.template_dirs)
+                .select(factsheet.web_framework, factsheet.project_scope.as_ref())
+                .expect("Failed to find a code template"),
+        };
+
+        let template_str = format!(
+            "Starting from the {} {} template",
+            template.framework, template.kind
+        );
+        PrintCommand::UnitTest.print_agent_msg(self.attributes.position.as_str(), &template_str);
+
         let mut code_template_content = String::new();
-        read_code_template_content()
+        read_code_template_content(&template)
             .expect("Failed to open code template")
             .read_to_string(&mut code_template_content)
             .expect("Failed to read code template content");
 
-        let msg_context = format!(
-            "CODE_TEMPLATE : {} \n PROJECT_DESCRIPTION: {} \n",
-            code_template_content, factsheet.project_description
+        // The generated project must depend on the framework the template is written with
+        let manifest =
+            read_framework_manifest(&template).expect("Failed to read framework manifest");
+        let manifest = factsheet.persistence.apply_to_manifest(&manifest);
+
+        let mut msg_context = format!(
+            "CODE_TEMPLATE : {} \n PROJECT_DESCRIPTION: {} \n FRAMEWORK: {} \n PERSISTENCE: {} \n INSTALLED_LIBRARIES: {} \n",
+            code_template_content,
+            factsheet.project_description,
+            factsheet.web_framework,
+            factsheet.persistence_instructions(),
+            factsheet.installed_libraries()
         );
 
+        // sqlx::migrate!() embeds the migrations when the server compiles, so they go first
+        if let Some(migration) = save_migrations(factsheet).expect("Failed to save migrations") {
+            msg_context.push_str(&format!("MIGRATIONS: {} \n", migration));
+        }
+
+        if let Some(data_model) = &factsheet.data_model {
+            msg_context.push_str(&format!(
+                "DATA_MODEL: {} \n",
+                serde_json::to_string(data_model).expect("Failed to serialize data model")
+            ));
+        }
+
         let response = perfom_ai_call(
             msg_context,
             &self.attributes.position,
@@ -56,16 +159,42 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        save_backend_code(&response).expect("Failed to save backend code");
-        factsheet.backend_code = Some(response);
+        self.store_backend_files(factsheet, &response, Some(manifest));
+        factsheet.code_template = Some(template);
     }
 
-    async fn call_improved_backend_code(&mut self, factsheet: &mut FactSheet) {
-        let msg_context = format!(
-            "CODE_TEMPLATE : {:?} \n PROJECT_DESCRIPTION: {:?} \n",
-            factsheet.backend_code, factsheet
+    // Returns the ai_function the code came from, patches or a full rewrite
+    async fn call_improved_backend_code(&mut self, factsheet: &mut FactSheet) -> &'static str {
+        let mut msg_context = format!(
+            "BACKEND_FILES : {} \n PROJECT_DESCRIPTION: {:?} \n INSTALLED_LIBRARIES: {} \n",
+            factsheet.backend_listing(),
+            factsheet,
+            factsheet.installed_libraries()
         );
 
+        if !self.review_findings.is_empty() {
+            msg_context.push_str(&format!("REVIEW_FINDINGS: {:?} \n", self.review_findings));
+        }
+
+        if let Some(amend_goal) = &self.amend_goal {
+            msg_context.push_str(&format!(
+                "AMEND_GOAL: {} \n EXISTING_ROUTES: {:?} \n",
+                amend_goal, self.existing_routes
+            ));
+        }
+
+        let is_patched = self
+            .call_patch_backend_files(
+                factsheet,
+                &msg_context,
+                get_function_string!(print_improved_webserver_patches),
+                print_improved_webserver_patches,
+            )
+            .await;
+        if is_patched {
+            return get_function_string!(print_improved_webserver_patches);
+        }
+
         let response = perfom_ai_call(
             msg_context,
             &self.attributes.position,
@@ -74,46 +203,529 @@ impl AgentBackendDeveloper {
         )
         .await;
 
-        save_backend_code(&response).expect("Failed to save backend code");
-        factsheet.backend_code = Some(response);
+        self.store_backend_files(factsheet, &response, None);
+        get_function_string!(print_improved_webserver_code)
     }
 
-    async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) {
+    async fn call_fix_code_bugs(&mut self, factsheet: &mut FactSheet) -> &'static str {
         let msg_context = format!(
-            "BROKEN_CODE : {:?} \n ERROR_BUGS: {:?} \n
-            THIS FUNCTION ONLY OUTPUTS CODE. JUST OUTPUT THE CODE",
-            factsheet.backend_code, self.bug_errors
+            "BROKEN_FILES : {} \n ERROR_BUGS: {:?} \n FRAMEWORK: {} \n PERSISTENCE: {} \n INSTALLED_LIBRARIES: {} \n",
+            factsheet.backend_listing(),
+            self.bug_errors,
+            factsheet.web_framework,
+            factsheet.persistence_instructions(),
+            factsheet.installed_libraries()
         );
 
+        let is_patched = self
+            .call_patch_backend_files(
+                factsheet,
+                &msg_context,
+                get_function_string!(print_fixed_code_patches),
+                print_fixed_code_patches,
+            )
+            .await;
+        if is_patched {
+            return get_function_string!(print_fixed_code_patches);
+        }
+
         let response = perfom_ai_call(
-            msg_context,
+            msg_context + "\n THIS FUNCTION ONLY OUTPUTS FILES. JUST OUTPUT THE FILES",
             &self.attributes.position,
             get_function_string!(print_fixed_code),
             print_fixed_code,
         )
         .await;
 
-        save_backend_code(&response).expect("Failed to save backend code");
-        factsheet.backend_code = Some(response);
+        self.store_backend_files(factsheet, &response, None);
+        get_function_string!(print_fixed_code)
     }
 
-    async fn call_extract_rest_api_endpoints(&self) -> String {
-        let mut exec_content = String::new();
-        read_exec_main_contents()
-            .expect("Failed to open exec main contents")
-            .read_to_string(&mut exec_content)
-            .expect("Failed to read exec main contents");
-
-        let msg_context = format!("CODE INPUT: {}", exec_content);
+    // Asks for search/replace patches instead of every file again. Returns false when they do
+    // not parse or apply, and the caller falls back to a full rewrite
+    async fn call_patch_backend_files(
+        &self,
+        factsheet: &mut FactSheet,
+        msg_context: &str,
+        agent_operation: &str,
+        func: for<'a> fn(&'a str) -> &'static str,
+    ) -> bool {
+        if factsheet.edit_mode != EditMode::Patch {
+            return false;
+        }
+        let Some(files) = factsheet.backend_files.as_ref() else {
+            return false;
+        };
 
         let response = perfom_ai_call(
+            msg_context.to_string(),
+            &self.attributes.position,
+            agent_operation,
+            func,
+        )
+        .await;
+
+        match parse_patches(&response).and_then(|patches| apply_patches(files, &patches)) {
+            Ok(patched) => {
+                save_backend_files(&patched).expect("Failed to save backend files");
+                factsheet.backend_files = Some(patched);
+                true
+            }
+            Err(error) => {
+                let issue = format!(
+                    "Patching failed ({}), falling back to a full rewrite",
+                    error
+                );
+                PrintCommand::Issue.print_agent_msg(self.attributes.position.as_str(), &issue);
+                false
+            }
+        }
+    }
+
+    // Parses the files the LLM printed and writes them into the web template. Cargo.toml comes
+    // from the template on the first pass and is carried over afterwards, as are the tests
+    fn store_backend_files(
+        &self,
+        factsheet: &mut FactSheet,
+        response: &str,
+        manifest: Option<String>,
+    ) {
+        // Output without any FILE header is taken to be a single main.rs
+        let (mut files, skipped) =
+            BackendFiles::parse_delimited(response).unwrap_or_else(|error| {
+                let mut files = BackendFiles::default();
+                files.insert(MAIN_FILE, extract_code_from_code_block(response));
+                (
+                    files,
+                    vec![format!("{}, saving the output as {}", error, MAIN_FILE)],
+                )
+            });
+        for issue in &skipped {
+            PrintCommand::Issue.print_agent_msg(self.attributes.position.as_str(), issue);
+        }
+
+        let previous = factsheet.backend_files.as_ref();
+        // A response that lost main.rs keeps the one from before rather than none at all
+        if files.get(MAIN_FILE).is_none() {
+            if let Some(main_code) = previous.and_then(|files| files.get(MAIN_FILE)) {
+                files.insert(MAIN_FILE, main_code);
+            }
+        }
+        let manifest = manifest.or_else(|| {
+            previous
+                .and_then(|files| files.get(MANIFEST_FILE))
+                .map(String::from)
+        });
+        if let Some(manifest) = manifest {
+            files.insert(MANIFEST_FILE, &manifest);
+        }
+
+        if files.get(TESTS_FILE).is_none() {
+            if let Some(tests) = previous.and_then(|files| files.get(TESTS_FILE)) {
+                files.insert_tests(tests);
+            }
+        }
+
+        save_backend_files(&files).expect("Failed to save backend files");
+        factsheet.backend_files = Some(files);
+    }
+
+    async fn call_extract_rest_api_endpoints(&self, factsheet: &FactSheet) -> Vec<RouteObject> {
+        let sources = factsheet
+            .backend_files
+            .as_ref()
+            .map(|files| {
+                let mut sources = BackendFiles::default();
+                for (path, content) in files.rust_sources() {
+                    sources.insert(path, content);
+                }
+                sources.to_delimited()
+            })
+            .unwrap_or_default();
+
+        let msg_context = format!(
+            "FRAMEWORK: {} \n CODE INPUT: {}",
+            factsheet.web_framework, sources
+        );
+
+        let mut routes = perfom_ai_call_decoded::<Vec<RouteObject>>(
             msg_context,
             &self.attributes.position,
             get_function_string!(print_rest_api_endpoints),
             print_rest_api_endpoints,
         )
         .await;
+        retain_api_routes(&mut routes);
+
+        routes
+    }
+
+    // Cross checks the LLM extracted routes against the ones parsed out of the code
+    fn check_route_extraction(
+        &self,
+        routes: &[RouteObject],
+        factsheet: &FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let files = factsheet
+            .backend_files
+            .as_ref()
+            .ok_or("No backend files to extract routes from")?;
+
+        let static_routes =
+            extract_routes_from_sources(files.rust_sources(), factsheet.web_framework)?;
+        let disagreements = compare_route_extractions(routes, &static_routes);
+
+        for disagreement in &disagreements {
+            PrintCommand::Issue
+                .print_agent_msg(self.attributes.position.as_str(), disagreement.as_str());
+        }
+
+        save_artifact(
+            ROUTE_DISAGREEMENTS_ARTIFACT,
+            &serde_json::to_string_pretty(&disagreements)?,
+        )?;
+
+        Ok(())
+    }
+
+    // Builds the generated Rust client and runs its smoke example against the live server.
+    // Returns the failure report when the server does not start or does not answer the way its
+    // schema says
+    async fn smoke_test_api_clients(
+        &self,
+        persistence: PersistenceBackend,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let build_output = build_nested_crate(RUST_CLIENT_PATH)?;
+        if !build_output.status.success() {
+            return Err(format!(
+                "Generated Rust client does not compile:\n{}",
+                command_failure_report(&build_output)
+            )
+            .into());
+        }
+
+        let server = match start_web_server(&Client::new(), persistence).await {
+            Ok(server) => server,
+            Err(err) => {
+                return Ok(Some(format!(
+                    "Web server did not start for the client smoke test: {}",
+                    err
+                )))
+            }
+        };
+        let smoke_output = run_nested_crate_example(RUST_CLIENT_PATH, RUST_CLIENT_SMOKE_EXAMPLE);
+        stop_web_server(server)?;
+        let smoke_output = smoke_output?;
+
+        if !smoke_output.status.success() {
+            return Ok(Some(command_failure_report(&smoke_output)));
+        }
+
+        PrintCommand::UnitTest.print_agent_msg(
+            self.attributes.position.as_str(),
+            "Generated API client passed the smoke test against the running server",
+        );
+
+        Ok(None)
+    }
+
+    // Returns true when blocking findings call for another improvement round
+    async fn call_review_backend_code(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut code_reviewer = AgentCodeReviewer::new();
+        code_reviewer.execute(factsheet).await?;
+
+        self.review_rounds += 1;
+        self.review_findings = code_reviewer.blocking_findings();
+
+        if self.review_findings.is_empty() {
+            return Ok(false);
+        }
+
+        if self.review_rounds >= MAX_REVIEW_ROUNDS {
+            PrintCommand::Issue.print_agent_msg(
+                self.attributes.position.as_str(),
+                "Blocking review findings remain, moving on to unit testing",
+            );
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    // Commits the code of a backend stage to the workspace history. Losing the history is not
+    // worth failing the run over, so problems are only reported
+    fn record_iteration(
+        &mut self,
+        factsheet: &FactSheet,
+        stage: &str,
+        ai_function: &'static str,
+        outcome: &str,
+    ) {
+        self.last_ai_function = ai_function;
+
+        let message = format!(
+            "{}: {} with {}, {}",
+            self.attributes.position, stage, ai_function, outcome
+        );
+        if let Err(err) = commit_backend_iteration(factsheet, &message) {
+            let issue = format!("Failed to record the {} code in git: {}", stage, err);
+            PrintCommand::Issue.print_agent_msg(self.attributes.position.as_str(), &issue);
+        }
+    }
+
+    fn record_bugs(&mut self, bug_errors: String) -> Result<(), Box<dyn std::error::Error>> {
+        self.bug_count += 1;
+
+        if self.bug_count > MAX_BUG_FIX_ATTEMPTS {
+            PrintCommand::Issue.print_agent_msg(
+                self.attributes.position.as_str(),
+                "Too many bugs found in code, giving up",
+            );
+            return Err(Box::new(ReplanTrigger::BugFixesExhausted(bug_errors)));
+        }
+
+        self.bug_errors = Some(bug_errors);
+        self.attributes.update_state(AgentState::Working);
+
+        Ok(())
+    }
+}
+
+// Lists the routes from before an amend that the new code no longer serves. Renamed path
+// params still count as the same route
+fn find_dropped_routes(existing_routes: &[RouteObject], routes: &[RouteObject]) -> Vec<String> {
+    let keys = routes.iter().map(route_key).collect::<Vec<_>>();
+    existing_routes
+        .iter()
+        .filter(|existing| !keys.contains(&route_key(existing)))
+        .map(|existing| {
+            format!(
+                "{} {} existed before the change and must be kept",
+                existing.method, existing.route
+            )
+        })
+        .collect()
+}
+
+// Builds the code just written so its commit can say whether it compiles. The build in
+// UnitTesting then only has to redo it if something changed since
+fn build_outcome() -> &'static str {
+    match build_web_server() {
+        Ok(output) if output.status.success() => "build passed",
+        Ok(_) => "build failed",
+        Err(_) => "build did not run",
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentBackendDeveloper {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectDescription,
+            FactField::ProjectScope,
+            FactField::ExternalUrls,
+            FactField::DataModel,
+            FactField::BackendFiles,
+            FactField::ApiEndpointSchema,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[
+            FactField::BackendFiles,
+            FactField::ApiEndpointSchema,
+            FactField::CodeTemplate,
+        ]
+    }
+
+    // The code reviewer and test writer it runs call their ai_functions with its settings
+    fn ai_functions(&self) -> &[&'static str] {
+        &[
+            get_function_string!(print_backend_webserver_code),
+            get_function_string!(print_improved_webserver_code),
+            get_function_string!(print_improved_webserver_patches),
+            get_function_string!(print_fixed_code),
+            get_function_string!(print_fixed_code_patches),
+            get_function_string!(print_rest_api_endpoints),
+            get_function_string!(print_code_review),
+            get_function_string!(print_backend_integration_tests),
+        ]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_initial_backend_code(factsheet).await;
+                    self.record_iteration(
+                        factsheet,
+                        "initial",
+                        get_function_string!(print_backend_webserver_code),
+                        build_outcome(),
+                    );
+                    self.attributes.update_state(AgentState::Working);
+                }
+
+                AgentState::Working => {
+                    if self.bug_count == 0 {
+                        let ai_function = self.call_improved_backend_code(factsheet).await;
+                        self.record_iteration(factsheet, "improved", ai_function, build_outcome());
+
+                        if self.call_review_backend_code(factsheet).await? {
+                            continue;
+                        }
+                    } else {
+                        let ai_function = self.call_fix_code_bugs(factsheet).await;
+                        let stage = format!("bug fix {}", self.bug_count);
+                        self.record_iteration(factsheet, &stage, ai_function, build_outcome());
+                    }
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Backend code unit testing: building project...",
+                    );
+
+                    let build_output = build_web_server()?;
+                    if !build_output.status.success() {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Backend code build failed, sending errors back for fixing",
+                        );
+                        self.record_bugs(command_failure_report(&build_output))?;
+                        continue;
+                    }
+
+                    let routes = self.call_extract_rest_api_endpoints(factsheet).await;
+                    self.check_route_extraction(&routes, factsheet)?;
+
+                    let dropped_routes = find_dropped_routes(&self.existing_routes, &routes);
+                    if !dropped_routes.is_empty() {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Amended code dropped existing routes, sending back for fixing",
+                        );
+                        self.record_bugs(dropped_routes.join("\n"))?;
+                        continue;
+                    }
+
+                    // Endpoints may return shapes of their own, so mismatches are only reported
+                    if let Some(data_model) = &factsheet.data_model {
+                        for mismatch in find_data_model_mismatches(&routes, data_model) {
+                            PrintCommand::Issue
+                                .print_agent_msg(self.attributes.position.as_str(), &mismatch);
+                        }
+                    }
+
+                    save_api_endpoints(&routes)?;
+                    factsheet.api_endpoint_schema = Some(routes);
+                    save_openapi_spec(factsheet)?;
+                    save_request_collections(factsheet)?;
+
+                    let mut test_writer = AgentTestWriter::new();
+                    test_writer.execute(factsheet).await?;
+
+                    if let Some(test_failures) = test_writer.test_failures() {
+                        self.record_bugs(test_failures.clone())?;
+                        continue;
+                    }
+
+                    save_api_clients(factsheet)?;
+                    if let Some(smoke_failures) =
+                        self.smoke_test_api_clients(factsheet.persistence).await?
+                    {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Generated API client smoke test failed, sending errors back for fixing",
+                        );
+                        self.record_bugs(smoke_failures)?;
+                        continue;
+                    }
+
+                    self.bug_errors = None;
+                    self.record_iteration(
+                        factsheet,
+                        "final",
+                        self.last_ai_function,
+                        "build and tests passed",
+                    );
+                    self.attributes.update_state(AgentState::Finished);
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::general::{
+        framework::WebFramework, persistence::PersistenceBackend, schema::HttpMethod,
+    };
+
+    #[test]
+    fn tests_find_dropped_routes() {
+        let existing_routes = vec![
+            RouteObject::new(HttpMethod::Get, "/task", None, None),
+            RouteObject::new(HttpMethod::Get, "/task/{id}", None, None),
+            RouteObject::new(HttpMethod::Delete, "/task/{id}", None, None),
+        ];
+        let routes = vec![
+            RouteObject::new(HttpMethod::Get, "/task", None, None),
+            RouteObject::new(HttpMethod::Get, "/task/{task_id}", None, None),
+            RouteObject::new(HttpMethod::Get, "/task/page/{page}", None, None),
+        ];
+
+        assert_eq!(
+            find_dropped_routes(&existing_routes, &routes),
+            vec!["delete /task/{id} existed before the change and must be kept"]
+        );
+        assert!(find_dropped_routes(&[], &routes).is_empty());
+    }
+
+    #[tokio::test]
+    async fn tests_backend_developer() {
+        let mut agent = AgentBackendDeveloper::new();
+
+        let mut factsheet = FactSheet {
+            project_description: "build a website that lets users track their daily tasks"
+                .to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        };
+
+        agent
+            .execute(&mut factsheet)
+            .await
+            .expect("Unable to execute backend developer agent");
 
-        response
+        assert!(factsheet.backend_files.is_some());
+        assert!(factsheet.api_endpoint_schema.is_some());
     }
 }
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai_functions::aifunc_reviewing::print_code_review,
+    helpers::{
+        cli::PrintCommand,
+        general::{perfom_ai_call_decoded, save_artifact},
+    },
+    models::agent_basic::{
+        basic_agent::{AgentState, BasicAgent},
+        basic_trait::BasicTraits,
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, SpecialFunctions};
+
+const CODE_REVIEW_ARTIFACT: &str = "code_review.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSeverity {
+    Blocking,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewFinding {
+    pub severity: ReviewSeverity,
+    pub category: String,
+    pub description: String,
+    #[serde(default)]
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct AgentCodeReviewer {
+    attributes: BasicAgent,
+    findings: Vec<ReviewFinding>,
+}
+
+impl AgentCodeReviewer {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Reviews generated backend code against the project factsheet".to_string(),
+            position: "Code reviewer".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            findings: vec![],
+        }
+    }
+
+    pub fn blocking_findings(&self) -> Vec<ReviewFinding> {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == ReviewSeverity::Blocking)
+            .cloned()
+            .collect()
+    }
+
+    async fn call_review_code(&mut self, factsheet: &FactSheet) {
+        let msg_context = format!(
+            "BACKEND_FILES: {} \n FACTSHEET: {{ project_description: {:?}, project_scope: {:?}, external_urls: {:?} }} \n",
+            factsheet.backend_listing(),
+            factsheet.project_description,
+            factsheet.project_scope,
+            factsheet.external_urls
+        );
+
+        self.findings = perfom_ai_call_decoded::<Vec<ReviewFinding>>(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_code_review),
+            print_code_review,
+        )
+        .await;
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentCodeReviewer {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectDescription,
+            FactField::ProjectScope,
+            FactField::ExternalUrls,
+            FactField::BackendFiles,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[get_function_string!(print_code_review)]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_review_code(factsheet).await;
+
+                    save_artifact(
+                        CODE_REVIEW_ARTIFACT,
+                        &serde_json::to_string_pretty(&self.findings)?,
+                    )?;
+
+                    let review_str = format!(
+                        "Review found {} issue(s), {} blocking",
+                        self.findings.len(),
+                        self.blocking_findings().len()
+                    );
+                    PrintCommand::UnitTest
+                        .print_agent_msg(self.attributes.position.as_str(), review_str.as_str());
+
+                    self.attributes.update_state(AgentState::Finished);
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_blocking_findings() {
+        let review = r#"[
+            {"severity": "blocking", "category": "plaintext_password", "description": "Passwords are stored as plain text"},
+            {"severity": "warning", "category": "error_handling", "description": "save_to_file result ignored", "suggestion": "Return a 500"}
+        ]"#;
+
+        let mut agent = AgentCodeReviewer::new();
+        agent.findings = serde_json::from_str(review).expect("Failed to decode review");
+
+        let blocking = agent.blocking_findings();
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].category, "plaintext_password");
+    }
+}
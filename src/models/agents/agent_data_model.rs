@@ -0,0 +1,308 @@
+use std::collections::{BTreeMap, HashSet};
+
+use async_trait::async_trait;
+
+use crate::{
+    ai_functions::aifunc_modeling::print_data_model,
+    helpers::{cli::PrintCommand, general::perfom_ai_call_decoded},
+    models::{
+        agent_basic::{
+            basic_agent::{AgentState, BasicAgent},
+            basic_trait::BasicTraits,
+        },
+        general::schema::TypeSchema,
+    },
+};
+
+use super::agent_traits::{
+    DataModel, Entity, FactField, FactSheet, FieldType, RouteObject, SpecialFunctions,
+};
+
+const MAX_DESIGN_ATTEMPTS: u8 = 2;
+
+#[derive(Debug)]
+pub struct AgentDataModelDesigner {
+    attributes: BasicAgent,
+    validation_errors: Vec<String>,
+    design_count: u8,
+}
+
+impl AgentDataModelDesigner {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Designs the entities, fields and relations the backend stores".to_string(),
+            position: "Data modeler".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            validation_errors: vec![],
+            design_count: 0,
+        }
+    }
+
+    async fn call_design_data_model(&mut self, factsheet: &mut FactSheet) {
+        let mut msg_context = format!(
+            "PROJECT_DESCRIPTION: {} \n PROJECT_SCOPE: {:?} \n",
+            factsheet.project_description, factsheet.project_scope
+        );
+
+        if !self.validation_errors.is_empty() {
+            msg_context.push_str(&format!(
+                "PREVIOUS_DATA_MODEL: {:?} \n VALIDATION_ERRORS: {:?} \n",
+                factsheet.data_model, self.validation_errors
+            ));
+        }
+
+        let data_model = perfom_ai_call_decoded::<DataModel>(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_data_model),
+            print_data_model,
+        )
+        .await;
+
+        factsheet.data_model = Some(data_model);
+        self.design_count += 1;
+    }
+}
+
+pub fn validate_data_model(data_model: &DataModel) -> Vec<String> {
+    let mut errors = vec![];
+    let mut names = HashSet::new();
+
+    for entity in &data_model.entities {
+        if !names.insert(entity.name.as_str()) {
+            errors.push(format!("Entity {} is defined twice", entity.name));
+        }
+
+        if !entity.fields.iter().any(|field| field.name == "id") {
+            errors.push(format!("Entity {} has no id field", entity.name));
+        }
+    }
+
+    for entity in &data_model.entities {
+        for relation in &entity.relations {
+            if !names.contains(relation.target.as_str()) {
+                errors.push(format!(
+                    "Entity {} relates to unknown entity {}",
+                    entity.name, relation.target
+                ));
+            }
+
+            if !entity
+                .fields
+                .iter()
+                .any(|field| field.name == relation.via_field)
+            {
+                errors.push(format!(
+                    "Entity {} has no field {} for its relation to {}",
+                    entity.name, relation.via_field, relation.target
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+fn is_field_type_compatible(schema_type: &TypeSchema, field_type: FieldType) -> bool {
+    match schema_type {
+        TypeSchema::Number => matches!(field_type, FieldType::Integer | FieldType::Float),
+        TypeSchema::String => matches!(
+            field_type,
+            FieldType::String | FieldType::DateTime | FieldType::Uuid
+        ),
+        TypeSchema::Bool => field_type == FieldType::Boolean,
+        _ => true,
+    }
+}
+
+fn matches_entity(fields: &BTreeMap<String, TypeSchema>, entity: &Entity) -> bool {
+    fields.iter().all(|(key, schema_type)| {
+        entity.fields.iter().any(|field| {
+            &field.name == key && is_field_type_compatible(schema_type, field.field_type)
+        })
+    })
+}
+
+fn body_mismatch(body: &TypeSchema, data_model: &DataModel) -> Option<Vec<String>> {
+    let fields = match body {
+        TypeSchema::Object(fields) => fields,
+        TypeSchema::Array(item) => return body_mismatch(item, data_model),
+        _ => return None,
+    };
+
+    // Bodies sharing no field with any entity, such as {"message": ...} or a count, are not
+    // meant to be entities
+    let is_entity_like = data_model.entities.iter().any(|entity| {
+        entity
+            .fields
+            .iter()
+            .any(|field| fields.contains_key(&field.name))
+    });
+    if !is_entity_like
+        || data_model
+            .entities
+            .iter()
+            .any(|entity| matches_entity(fields, entity))
+    {
+        return None;
+    }
+
+    Some(fields.keys().cloned().collect())
+}
+
+// Lists every request body or response that looks like an entity but whose fields fit none
+pub fn find_data_model_mismatches(routes: &[RouteObject], data_model: &DataModel) -> Vec<String> {
+    let mut mismatches = vec![];
+
+    for route in routes {
+        for (part, body) in [
+            ("request body", &route.request_body),
+            ("response", &route.response),
+        ] {
+            if let Some(fields) = body
+                .as_ref()
+                .and_then(|body| body_mismatch(body, data_model))
+            {
+                mismatches.push(format!(
+                    "{} of {} {} has fields {:?} that match no entity in the data model",
+                    part, route.method, route.route, fields
+                ));
+            }
+        }
+    }
+
+    mismatches
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentDataModelDesigner {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[FactField::ProjectDescription, FactField::ProjectScope]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[FactField::DataModel]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[get_function_string!(print_data_model)]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery | AgentState::Working => {
+                    self.call_design_data_model(factsheet).await;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    let data_model = factsheet.data_model.as_ref().ok_or("No data model found")?;
+                    self.validation_errors = validate_data_model(data_model);
+
+                    if self.validation_errors.is_empty() {
+                        let model_str = format!("Designed {} entities", data_model.entities.len());
+                        PrintCommand::UnitTest
+                            .print_agent_msg(self.attributes.position.as_str(), model_str.as_str());
+                        self.attributes.update_state(AgentState::Finished);
+                    } else if self.design_count < MAX_DESIGN_ATTEMPTS {
+                        let issue_str = format!("Data model issues: {:?}", self.validation_errors);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), issue_str.as_str());
+                        self.attributes.update_state(AgentState::Working);
+                    } else {
+                        return Err("Unable to design a valid data model".into());
+                    }
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn task_data_model() -> DataModel {
+        serde_json::from_value(json!({
+            "entities": [
+                {
+                    "name": "Task",
+                    "fields": [
+                        { "name": "id", "field_type": "integer" },
+                        { "name": "name", "field_type": "string" },
+                        { "name": "completed", "field_type": "boolean" },
+                        { "name": "user_id", "field_type": "integer" }
+                    ],
+                    "relations": [{ "kind": "many_to_one", "target": "User", "via_field": "user_id" }]
+                },
+                {
+                    "name": "User",
+                    "fields": [
+                        { "name": "id", "field_type": "integer" },
+                        { "name": "username", "field_type": "string" }
+                    ]
+                }
+            ]
+        }))
+        .expect("Failed to decode data model")
+    }
+
+    #[test]
+    fn tests_validate_data_model() {
+        let mut data_model = task_data_model();
+        assert!(validate_data_model(&data_model).is_empty());
+
+        data_model.entities[1].fields.remove(0);
+        data_model.entities[0].relations[0].target = "Account".to_string();
+        assert_eq!(validate_data_model(&data_model).len(), 2);
+    }
+
+    #[test]
+    fn tests_find_data_model_mismatches() {
+        let routes: Vec<RouteObject> = serde_json::from_value(json!([
+            {
+                "route": "/task/{id}",
+                "method": "get",
+                "request_body": "None",
+                "response": [{ "id": "number", "name": "string", "completed": "bool" }]
+            },
+            {
+                "route": "/task",
+                "method": "post",
+                "request_body": { "id": "number", "title": "string" },
+                "response": { "message": "string" }
+            },
+            {
+                "route": "/task/count",
+                "method": "get",
+                "request_body": "None",
+                "response": { "count": "number" }
+            }
+        ]))
+        .expect("Failed to decode routes");
+
+        let mismatches = find_data_model_mismatches(&routes, &task_data_model());
+
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].contains("post /task"));
+    }
+}
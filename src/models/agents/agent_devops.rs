@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use crate::{
+    ai_functions::aifunc_devops::{
+        print_docker_compose, print_dockerfile, print_env_example, print_fixed_devops_file,
+        print_makefile,
+    },
+    helpers::{
+        cli::PrintCommand,
+        general::{extract_code_from_code_block, perfom_ai_call},
+        lint::lint_devops_file,
+        web_server::{build_web_server, save_web_server_file},
+    },
+    models::agent_basic::{
+        basic_agent::{AgentState, BasicAgent},
+        basic_trait::BasicTraits,
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, SpecialFunctions};
+
+const DEVOPS_FILES: [&str; 4] = [
+    "Dockerfile",
+    "docker-compose.yml",
+    ".env.example",
+    "Makefile",
+];
+const MAX_FIX_ATTEMPTS: u8 = 2;
+
+#[derive(Debug)]
+pub struct AgentDevOps {
+    attributes: BasicAgent,
+    files: BTreeMap<String, String>,
+    lint_errors: BTreeMap<String, Vec<String>>,
+    fix_count: u8,
+}
+
+impl AgentDevOps {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Packages the backend webserver with Docker, compose and make".to_string(),
+            position: "DevOps engineer".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            files: BTreeMap::new(),
+            lint_errors: BTreeMap::new(),
+            fix_count: 0,
+        }
+    }
+
+    async fn call_devops_file(&mut self, factsheet: &FactSheet, file_name: &str) {
+        let msg_context = format!(
+            "BACKEND_FILES: {} \n PROJECT_DESCRIPTION: {} \n PERSISTENCE: {} \n HAS_MIGRATIONS: {} \n",
+            factsheet.backend_listing(),
+            factsheet.project_description,
+            factsheet.persistence,
+            factsheet.has_migrations()
+        );
+
+        let (agent_operation, func): (&str, fn(&str) -> &'static str) = match file_name {
+            "Dockerfile" => (get_function_string!(print_dockerfile), print_dockerfile),
+            "docker-compose.yml" => (
+                get_function_string!(print_docker_compose),
+                print_docker_compose,
+            ),
+            ".env.example" => (get_function_string!(print_env_example), print_env_example),
+            _ => (get_function_string!(print_makefile), print_makefile),
+        };
+
+        let response = perfom_ai_call(
+            msg_context,
+            &self.attributes.position,
+            agent_operation,
+            func,
+        )
+        .await;
+
+        self.save_file(file_name, extract_code_from_code_block(&response));
+    }
+
+    async fn call_fix_devops_file(&mut self, file_name: &str) {
+        let msg_context = format!(
+            "FILE_NAME: {} \n FILE_CONTENT: {:?} \n LINT_ERRORS: {:?} \n",
+            file_name, self.files[file_name], self.lint_errors[file_name]
+        );
+
+        let response = perfom_ai_call(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_fixed_devops_file),
+            print_fixed_devops_file,
+        )
+        .await;
+
+        self.save_file(file_name, extract_code_from_code_block(&response));
+    }
+
+    fn save_file(&mut self, file_name: &str, content: &str) {
+        // The code block extraction trims the trailing newline make and docker expect
+        let content = format!("{}\n", content);
+        save_web_server_file(file_name, &content).expect("Failed to save devops file");
+        self.files.insert(file_name.to_string(), content);
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentDevOps {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectDescription,
+            FactField::BackendFiles,
+            FactField::DataModel,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[FactField::DevopsArtifacts]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[
+            get_function_string!(print_dockerfile),
+            get_function_string!(print_docker_compose),
+            get_function_string!(print_env_example),
+            get_function_string!(print_makefile),
+            get_function_string!(print_fixed_devops_file),
+        ]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    if !build_web_server()?.status.success() {
+                        return Err("Backend must compile before packaging it".into());
+                    }
+
+                    for file_name in DEVOPS_FILES {
+                        self.call_devops_file(factsheet, file_name).await;
+                    }
+
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::Working => {
+                    let broken_files: Vec<String> = self.lint_errors.keys().cloned().collect();
+                    for file_name in broken_files {
+                        self.call_fix_devops_file(&file_name).await;
+                    }
+
+                    self.fix_count += 1;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    self.lint_errors = self
+                        .files
+                        .iter()
+                        .map(|(file_name, content)| {
+                            (
+                                file_name.clone(),
+                                lint_devops_file(file_name, content, factsheet.persistence),
+                            )
+                        })
+                        .filter(|(_, errors)| !errors.is_empty())
+                        .collect();
+
+                    for (file_name, errors) in &self.lint_errors {
+                        let issue_str = format!("{} lint errors: {:?}", file_name, errors);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), issue_str.as_str());
+                    }
+
+                    if !self.lint_errors.is_empty() && self.fix_count < MAX_FIX_ATTEMPTS {
+                        self.attributes.update_state(AgentState::Working);
+                        continue;
+                    }
+
+                    if self.lint_errors.is_empty() {
+                        PrintCommand::UnitTest.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "DevOps files passed linting",
+                        );
+                    }
+
+                    factsheet.devops_artifacts = Some(self.files.keys().cloned().collect());
+                    self.attributes.update_state(AgentState::Finished);
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+
+use crate::{
+    ai_functions::aifunc_frontend::{print_fixed_frontend_code, print_frontend_webpage_code},
+    helpers::{
+        cli::PrintCommand,
+        general::{extract_code_from_code_block, perfom_ai_call, save_frontend_code},
+    },
+    models::agent_basic::{
+        basic_agent::{AgentState, BasicAgent},
+        basic_trait::BasicTraits,
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, RouteObject, SpecialFunctions};
+
+const MAX_FIX_ATTEMPTS: u8 = 2;
+
+#[derive(Debug)]
+pub struct AgentFrontendDeveloper {
+    attributes: BasicAgent,
+    missing_routes: Vec<String>,
+    fix_count: u8,
+}
+
+impl AgentFrontendDeveloper {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Develops a single page frontend that calls the backend api endpoints"
+                .to_string(),
+            position: "Frontend developer".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            missing_routes: vec![],
+            fix_count: 0,
+        }
+    }
+
+    async fn call_initial_frontend_code(&mut self, factsheet: &mut FactSheet) {
+        let endpoints = serde_json::to_string(&factsheet.api_endpoint_schema)
+            .expect("Failed to serialize api endpoint schema");
+
+        let msg_context = format!(
+            "PROJECT_DESCRIPTION: {} \n API_ENDPOINT_SCHEMA: {} \n",
+            factsheet.project_description, endpoints
+        );
+
+        let response = perfom_ai_call(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_frontend_webpage_code),
+            print_frontend_webpage_code,
+        )
+        .await;
+
+        let code = extract_code_from_code_block(&response).to_string();
+        save_frontend_code(&code).expect("Failed to save frontend code");
+        factsheet.frontend_code = Some(code);
+    }
+
+    async fn call_fix_frontend_code(&mut self, factsheet: &mut FactSheet) {
+        let msg_context = format!(
+            "FRONTEND_CODE: {:?} \n MISSING_ROUTES: {:?} \n",
+            factsheet.frontend_code, self.missing_routes
+        );
+
+        let response = perfom_ai_call(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_fixed_frontend_code),
+            print_fixed_frontend_code,
+        )
+        .await;
+
+        let code = extract_code_from_code_block(&response).to_string();
+        save_frontend_code(&code).expect("Failed to save frontend code");
+        factsheet.frontend_code = Some(code);
+    }
+}
+
+// Routes whose static prefix (everything before the first {param}) never appears in the page
+fn find_missing_routes(frontend_code: &str, routes: &[RouteObject]) -> Vec<String> {
+    routes
+        .iter()
+        .filter(|route| {
+            let prefix = route.route.split('{').next().unwrap_or_default();
+            let prefix = prefix.trim_end_matches('/');
+            !prefix.is_empty() && !frontend_code.contains(prefix)
+        })
+        .map(|route| format!("{} {}", route.method, route.route))
+        .collect()
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentFrontendDeveloper {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectDescription,
+            FactField::ApiEndpointSchema,
+            FactField::FrontendCode,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[FactField::FrontendCode]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[
+            get_function_string!(print_frontend_webpage_code),
+            get_function_string!(print_fixed_frontend_code),
+        ]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_initial_frontend_code(factsheet).await;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::Working => {
+                    self.call_fix_frontend_code(factsheet).await;
+                    self.fix_count += 1;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    let code = factsheet
+                        .frontend_code
+                        .as_ref()
+                        .ok_or("No frontend code found")?;
+
+                    if !code.contains("<html") {
+                        return Err("Generated frontend is not an HTML document".into());
+                    }
+
+                    let routes = factsheet.api_endpoint_schema.as_deref().unwrap_or_default();
+                    self.missing_routes = find_missing_routes(code, routes);
+
+                    if self.missing_routes.is_empty() {
+                        PrintCommand::UnitTest.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Frontend calls every api endpoint",
+                        );
+                        self.attributes.update_state(AgentState::Finished);
+                    } else if self.fix_count < MAX_FIX_ATTEMPTS {
+                        let issue =
+                            format!("Frontend is missing routes: {:?}", self.missing_routes);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), issue.as_str());
+                        self.attributes.update_state(AgentState::Working);
+                    } else {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Giving up on missing routes, keeping the current frontend",
+                        );
+                        self.attributes.update_state(AgentState::Finished);
+                    }
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::general::{
+        edit_mode::EditMode, framework::WebFramework, persistence::PersistenceBackend,
+        schema::HttpMethod,
+    };
+
+    use super::*;
+
+    fn route(method: HttpMethod, path: &str) -> RouteObject {
+        RouteObject::new(method, path, None, None)
+    }
+
+    #[test]
+    fn tests_find_missing_routes() {
+        let code = r#"<html><script>fetch("/task/" + id); fetch("/login")</script></html>"#;
+        let routes = vec![
+            route(HttpMethod::Get, "/task/{id}"),
+            route(HttpMethod::Post, "/login"),
+            route(HttpMethod::Post, "/register"),
+        ];
+
+        assert_eq!(find_missing_routes(code, &routes), vec!["post /register"]);
+    }
+
+    #[tokio::test]
+    async fn tests_frontend_developer() {
+        let mut agent = AgentFrontendDeveloper::new();
+
+        let mut factsheet = FactSheet {
+            project_description: "build a website that lets users manage a todo list".to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: None,
+            api_endpoint_schema: Some(vec![
+                route(HttpMethod::Post, "/task"),
+                route(HttpMethod::Get, "/task"),
+                route(HttpMethod::Get, "/task/{id}"),
+                route(HttpMethod::Delete, "/task/{id}"),
+            ]),
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        };
+
+        agent
+            .execute(&mut factsheet)
+            .await
+            .expect("Unable to execute frontend developer agent");
+
+        assert!(factsheet.frontend_code.is_some());
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    ffi::{c_char, CStr, CString},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use libloading::Library;
+use serde::Deserialize;
+
+use crate::{
+    helpers::cli::PrintCommand,
+    models::agent_basic::{
+        basic_agent::{AgentState, BasicAgent},
+        basic_trait::BasicTraits,
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, SpecialFunctions};
+
+// Bumped whenever the exported functions or the JSON they exchange change shape
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+// A plugin is a cdylib exporting these C functions. Strings are NUL terminated UTF-8, and
+// every string the plugin returns is handed back to rustptty_free_string:
+//   uint32_t rustptty_abi_version(void);
+//   char *rustptty_agent_manifest(void);
+//     {"name": "...", "description": "...", "position": 70, "reads": [...], "writes": [...]}
+//   char *rustptty_agent_execute(const char *factsheet_json);
+//     {"factsheet": {...}} with the FactSheet after the agent ran, or {"error": "..."}
+//   void rustptty_free_string(char *);
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *mut c_char;
+type ExecuteFn = unsafe extern "C" fn(*const c_char) -> *mut c_char;
+type FreeStringFn = unsafe extern "C" fn(*mut c_char);
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    pub name: String,
+    pub description: String,
+    pub position: u32,
+    #[serde(default)]
+    pub reads: Vec<FactField>,
+    #[serde(default)]
+    pub writes: Vec<FactField>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PluginOutput {
+    Factsheet(Box<FactSheet>),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Plugin {
+    path: PathBuf,
+    library: Library,
+    manifest: PluginManifest,
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let error = |error: String| format!("Plugin {}: {}", path.display(), error);
+
+        // Loading runs the library's initialisers, plugins are trusted like the rest of agents.toml
+        let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+
+        let version = unsafe {
+            let abi_version = library
+                .get::<AbiVersionFn>(b"rustptty_abi_version\0")
+                .map_err(|e| error(e.to_string()))?;
+            abi_version()
+        };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(error(format!(
+                "built for ABI version {}, expected {}",
+                version, PLUGIN_ABI_VERSION
+            )));
+        }
+
+        let manifest = unsafe {
+            let manifest = library
+                .get::<ManifestFn>(b"rustptty_agent_manifest\0")
+                .map_err(|e| error(e.to_string()))?;
+            take_string(&library, manifest())
+        }
+        .map_err(error)?;
+        let manifest = serde_json::from_str(&manifest).map_err(|e| error(e.to_string()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            library,
+            manifest,
+        })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    pub fn execute(&self, factsheet: &FactSheet) -> Result<FactSheet, String> {
+        let error = |error: String| format!("Plugin {}: {}", self.path.display(), error);
+
+        let input = serde_json::to_string(factsheet).map_err(|e| error(e.to_string()))?;
+        let input = CString::new(input).map_err(|e| error(e.to_string()))?;
+
+        let output = unsafe {
+            let execute = self
+                .library
+                .get::<ExecuteFn>(b"rustptty_agent_execute\0")
+                .map_err(|e| error(e.to_string()))?;
+            take_string(&self.library, execute(input.as_ptr()))
+        }
+        .map_err(error)?;
+
+        match serde_json::from_str(&output).map_err(|e| error(e.to_string()))? {
+            PluginOutput::Factsheet(factsheet) => Ok(*factsheet),
+            PluginOutput::Error(message) => Err(error(message)),
+        }
+    }
+}
+
+// Copies a string the plugin returned and hands it back to the plugin to free
+unsafe fn take_string(library: &Library, raw: *mut c_char) -> Result<String, String> {
+    if raw.is_null() {
+        return Err("returned no output".to_string());
+    }
+
+    let content = CStr::from_ptr(raw).to_string_lossy().into_owned();
+    let free = library
+        .get::<FreeStringFn>(b"rustptty_free_string\0")
+        .map_err(|e| e.to_string())?;
+    free(raw);
+
+    Ok(content)
+}
+
+// Runs a loaded plugin as one of the pipeline agents
+#[derive(Debug)]
+pub struct AgentPlugin {
+    attributes: BasicAgent,
+    plugin: Arc<Plugin>,
+}
+
+impl AgentPlugin {
+    pub fn new(plugin: Arc<Plugin>) -> Self {
+        let manifest = plugin.manifest();
+        let attributes = BasicAgent::new(&manifest.description, &manifest.name);
+
+        Self { attributes, plugin }
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentPlugin {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &self.plugin.manifest().reads
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &self.plugin.manifest().writes
+    }
+
+    // Plugins call their models themselves, if at all
+    fn ai_functions(&self) -> &[&'static str] {
+        &[]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        PrintCommand::AICall.print_agent_msg(
+            &self.attributes.position,
+            &format!("Running plugin {}", self.plugin.path.display()),
+        );
+
+        let plugin = self.plugin.clone();
+        let input = factsheet.clone();
+        let output = tokio::task::spawn_blocking(move || plugin.execute(&input)).await??;
+
+        // Only the fields in writes are merged back, whatever else the plugin changed
+        *factsheet = output;
+        self.attributes.update_state(AgentState::Finished);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::general::{
+        backend_files::{BackendFiles, MAIN_FILE},
+        edit_mode::EditMode,
+        framework::WebFramework,
+        persistence::PersistenceBackend,
+    };
+    use std::{
+        env::consts::{DLL_PREFIX, DLL_SUFFIX},
+        process::Command,
+    };
+
+    fn factsheet(main_code: &str) -> FactSheet {
+        let mut files = BackendFiles::default();
+        files.insert(MAIN_FILE, main_code);
+
+        FactSheet {
+            project_description: "build a website that lets users track their daily tasks"
+                .to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: Some(files),
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        }
+    }
+
+    #[tokio::test]
+    async fn tests_plugin_agent() {
+        let plugin_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/plugins/todo_checker");
+        let target_dir =
+            std::env::temp_dir().join(format!("rustptty_plugin_{}", std::process::id()));
+        let status = Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--offline", "--manifest-path"])
+            .arg(plugin_dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .expect("Failed to run cargo");
+        assert!(status.success());
+
+        let library = target_dir.join(format!("debug/{}todo_checker{}", DLL_PREFIX, DLL_SUFFIX));
+        let plugin = Arc::new(Plugin::load(&library).expect("Failed to load plugin"));
+        assert_eq!(
+            plugin.manifest(),
+            &PluginManifest {
+                name: "todo_checker".to_string(),
+                description: "Fails the run when the backend has todo!() or unimplemented!() left"
+                    .to_string(),
+                position: 35,
+                reads: vec![FactField::BackendFiles],
+                writes: vec![],
+            }
+        );
+
+        let mut agent = AgentPlugin::new(plugin.clone());
+        let mut finished = factsheet("fn main() {}\n");
+        agent
+            .execute(&mut finished)
+            .await
+            .expect("Failed to run plugin");
+        assert_eq!(
+            finished.backend_files,
+            factsheet("fn main() {}\n").backend_files
+        );
+        assert_eq!(agent.reads(), &[FactField::BackendFiles]);
+
+        let error = plugin
+            .execute(&factsheet("fn main() { todo!() }\n"))
+            .expect_err("Unfinished code passed");
+        assert!(error.ends_with("Unfinished code in src/main.rs"));
+        assert!(Plugin::load(&target_dir.join("missing.so")).is_err());
+
+        std::fs::remove_dir_all(&target_dir).expect("Failed to clean up plugin build");
+    }
+}
@@ -0,0 +1,482 @@
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ai_functions::aifunc_security::print_security_audit,
+    helpers::{
+        cli::PrintCommand,
+        general::{perfom_ai_call_decoded, save_artifact},
+        web_server::{read_web_server_database, start_web_server, stop_web_server, WEB_SERVER_URL},
+    },
+    models::{
+        agent_basic::{
+            basic_agent::{AgentState, BasicAgent},
+            basic_trait::BasicTraits,
+        },
+        general::{
+            persistence::PersistenceBackend,
+            schema::{HttpMethod, TypeSchema},
+        },
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, RouteObject, SpecialFunctions};
+
+const SECURITY_REPORT_ARTIFACT: &str = "security_report.json";
+const PROBE_ORIGIN: &str = "https://rustptty-probe.invalid";
+const PROBE_USERNAME: &str = "rustptty_probe_user";
+const PROBE_PASSWORD: &str = "rustptty-Probe-P4ssword";
+const TRAVERSAL_SEGMENT: &str = "..%2F..%2Fdatabase.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckSource {
+    #[default]
+    LlmReview,
+    LiveProbe,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecurityCheck {
+    pub check: String,
+    #[serde(default)]
+    pub source: CheckSource,
+    pub passed: bool,
+    pub details: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecurityReport {
+    pub passed: bool,
+    pub checks: Vec<SecurityCheck>,
+}
+
+#[derive(Debug)]
+pub struct AgentSecurityAuditor {
+    attributes: BasicAgent,
+    checks: Vec<SecurityCheck>,
+}
+
+impl AgentSecurityAuditor {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Audits authentication and input handling of the backend webserver"
+                .to_string(),
+            position: "Security auditor".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            checks: vec![],
+        }
+    }
+
+    async fn call_security_review(&mut self, factsheet: &FactSheet) {
+        let msg_context = format!("BACKEND_FILES: {}", factsheet.backend_listing());
+
+        let review = perfom_ai_call_decoded::<Vec<SecurityCheck>>(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_security_audit),
+            print_security_audit,
+        )
+        .await;
+
+        self.checks
+            .extend(review.into_iter().map(|check| SecurityCheck {
+                source: CheckSource::LlmReview,
+                ..check
+            }));
+    }
+
+    async fn run_live_probes(
+        &mut self,
+        routes: &[RouteObject],
+        persistence: PersistenceBackend,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .build()?;
+
+        let server = match start_web_server(&client, persistence).await {
+            Ok(server) => server,
+            Err(e) => {
+                self.checks.push(live_check(
+                    "live_probes",
+                    false,
+                    format!("Server could not be started for probing: {}", e),
+                ));
+                return Ok(());
+            }
+        };
+
+        self.checks.push(probe_cors(&client).await);
+
+        // Signs the probe user up first so the traversal probe can look for it in responses
+        if let Some(check) = probe_password_hashing(&client, routes, persistence).await {
+            self.checks.push(check);
+        }
+        self.checks
+            .push(probe_input_validation(&client, routes).await);
+        self.checks
+            .push(probe_path_traversal(&client, routes).await);
+
+        stop_web_server(server)?;
+
+        Ok(())
+    }
+}
+
+fn live_check(check: &str, passed: bool, details: String) -> SecurityCheck {
+    SecurityCheck {
+        check: check.to_string(),
+        source: CheckSource::LiveProbe,
+        passed,
+        details,
+    }
+}
+
+// Fills {param} segments so dynamic routes can be requested
+fn fill_route(route: &str, segment: &str) -> String {
+    route
+        .split('/')
+        .map(|part| if part.starts_with('{') { segment } else { part })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Builds a request body from the extracted schema, using the probe credentials where they fit
+fn synthesize_probe_body(schema: Option<&TypeSchema>) -> Value {
+    let Some(TypeSchema::Object(fields)) = schema else {
+        return json!({ "username": PROBE_USERNAME, "password": PROBE_PASSWORD });
+    };
+
+    let body: Map<String, Value> = fields
+        .iter()
+        .map(|(key, field_type)| {
+            let key_lower = key.to_lowercase();
+            let value = if key_lower.contains("password") {
+                json!(PROBE_PASSWORD)
+            } else if key_lower.contains("user") || key_lower.contains("name") {
+                json!(PROBE_USERNAME)
+            } else {
+                match field_type {
+                    TypeSchema::Number => json!(4242),
+                    TypeSchema::Bool => json!(false),
+                    TypeSchema::Object(_) => synthesize_probe_body(Some(field_type)),
+                    TypeSchema::Array(_) => json!([]),
+                    _ => json!("probe"),
+                }
+            };
+            (key.clone(), value)
+        })
+        .collect();
+
+    Value::Object(body)
+}
+
+fn is_cors_origin_allowed(allow_origin: Option<&str>) -> bool {
+    matches!(allow_origin, Some(origin) if origin == "*" || origin == PROBE_ORIGIN)
+}
+
+async fn probe_cors(client: &Client) -> SecurityCheck {
+    let response = client
+        .request(Method::OPTIONS, WEB_SERVER_URL)
+        .header("Origin", PROBE_ORIGIN)
+        .header("Access-Control-Request-Method", "POST")
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let allow_origin = response
+                .headers()
+                .get("access-control-allow-origin")
+                .and_then(|value| value.to_str().ok());
+
+            if is_cors_origin_allowed(allow_origin) {
+                live_check(
+                    "cors",
+                    false,
+                    format!(
+                        "Preflight from untrusted origin {} was allowed",
+                        PROBE_ORIGIN
+                    ),
+                )
+            } else {
+                live_check(
+                    "cors",
+                    true,
+                    "Preflight from an untrusted origin was rejected".to_string(),
+                )
+            }
+        }
+        Err(e) => live_check("cors", false, format!("Preflight request failed: {}", e)),
+    }
+}
+
+async fn probe_password_hashing(
+    client: &Client,
+    routes: &[RouteObject],
+    persistence: PersistenceBackend,
+) -> Option<SecurityCheck> {
+    let signup = routes.iter().find(|route| {
+        route.method == HttpMethod::Post
+            && ["register", "signup", "sign_up", "user"]
+                .iter()
+                .any(|name| route.route.to_lowercase().contains(name))
+    })?;
+
+    let url = format!("{}{}", WEB_SERVER_URL, fill_route(&signup.route, "1"));
+    if let Err(e) = client
+        .post(url)
+        .json(&synthesize_probe_body(signup.request_body.as_ref()))
+        .send()
+        .await
+    {
+        return Some(live_check(
+            "password_hashing",
+            false,
+            format!("Signup request to {} failed: {}", signup.route, e),
+        ));
+    }
+
+    let database = match read_web_server_database(persistence) {
+        Ok(database) => database,
+        Err(e) => {
+            return Some(live_check(
+                "password_hashing",
+                false,
+                format!(
+                    "Could not read the {} database to check: {}",
+                    persistence, e
+                ),
+            ))
+        }
+    };
+    if database.contains(PROBE_PASSWORD) {
+        Some(live_check(
+            "password_hashing",
+            false,
+            format!("Password sent to {} was stored as plain text", signup.route),
+        ))
+    } else {
+        Some(live_check(
+            "password_hashing",
+            true,
+            "Signup password was not found in the database".to_string(),
+        ))
+    }
+}
+
+async fn probe_input_validation(client: &Client, routes: &[RouteObject]) -> SecurityCheck {
+    let mut failures = vec![];
+
+    for route in routes
+        .iter()
+        .filter(|route| matches!(route.method, HttpMethod::Post | HttpMethod::Put))
+    {
+        let url = format!("{}{}", WEB_SERVER_URL, fill_route(&route.route, "1"));
+        let method = Method::from_bytes(route.method.as_str().to_uppercase().as_bytes())
+            .unwrap_or(Method::POST);
+
+        let response = client
+            .request(method, url)
+            .header("Content-Type", "application/json")
+            .body("{\"unexpected\":")
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_client_error() => {}
+            Ok(response) => failures.push(format!(
+                "{} {} answered malformed JSON with {}",
+                route.method,
+                route.route,
+                response.status()
+            )),
+            Err(e) => failures.push(format!("{} {} failed: {}", route.method, route.route, e)),
+        }
+    }
+
+    if failures.is_empty() {
+        live_check(
+            "input_validation",
+            true,
+            "Malformed request bodies were rejected with 4xx responses".to_string(),
+        )
+    } else {
+        live_check("input_validation", false, failures.join("; "))
+    }
+}
+
+async fn probe_path_traversal(client: &Client, routes: &[RouteObject]) -> SecurityCheck {
+    let mut paths = vec![
+        "/database.json".to_string(),
+        format!("/static/{}", TRAVERSAL_SEGMENT),
+    ];
+    paths.extend(
+        routes
+            .iter()
+            .filter(|route| route.method == HttpMethod::Get && route.is_route_dynamic)
+            .map(|route| fill_route(&route.route, TRAVERSAL_SEGMENT)),
+    );
+
+    let mut leaks = vec![];
+    for path in paths {
+        let Ok(response) = client
+            .get(format!("{}{}", WEB_SERVER_URL, path))
+            .send()
+            .await
+        else {
+            continue;
+        };
+
+        if response.status().is_success()
+            && response
+                .text()
+                .await
+                .unwrap_or_default()
+                .contains(PROBE_USERNAME)
+        {
+            leaks.push(path);
+        }
+    }
+
+    if leaks.is_empty() {
+        live_check(
+            "path_traversal",
+            true,
+            "The database file could not be read through any route".to_string(),
+        )
+    } else {
+        live_check(
+            "path_traversal",
+            false,
+            format!("Database contents were served at {:?}", leaks),
+        )
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentSecurityAuditor {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectScope,
+            FactField::BackendFiles,
+            FactField::ApiEndpointSchema,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[get_function_string!(print_security_audit)]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    let needs_audit = factsheet
+                        .project_scope
+                        .is_some_and(|scope| scope.is_user_login_and_logout);
+
+                    if !needs_audit {
+                        PrintCommand::UnitTest.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "No user login in project scope, skipping security audit",
+                        );
+                        self.attributes.update_state(AgentState::Finished);
+                        continue;
+                    }
+
+                    self.call_security_review(factsheet).await;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Probing the running webserver",
+                    );
+
+                    let routes = factsheet.api_endpoint_schema.clone().unwrap_or_default();
+                    self.run_live_probes(&routes, factsheet.persistence).await?;
+
+                    let report = SecurityReport {
+                        passed: self.checks.iter().all(|check| check.passed),
+                        checks: self.checks.clone(),
+                    };
+                    save_artifact(
+                        SECURITY_REPORT_ARTIFACT,
+                        &serde_json::to_string_pretty(&report)?,
+                    )?;
+
+                    for check in report.checks.iter().filter(|check| !check.passed) {
+                        let issue_str = format!("{} failed: {}", check.check, check.details);
+                        PrintCommand::Issue
+                            .print_agent_msg(self.attributes.position.as_str(), issue_str.as_str());
+                    }
+
+                    let verdict = if report.passed { "PASSED" } else { "FAILED" };
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        format!("Security audit {}", verdict).as_str(),
+                    );
+
+                    self.attributes.update_state(AgentState::Finished);
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_synthesize_probe_body() {
+        let schema: TypeSchema = serde_json::from_value(
+            json!({ "id": "number", "username": "string", "password": "string", "active": "bool" }),
+        )
+        .expect("Failed to decode schema");
+
+        let body = synthesize_probe_body(Some(&schema));
+
+        assert_eq!(body["id"], json!(4242));
+        assert_eq!(body["username"], json!(PROBE_USERNAME));
+        assert_eq!(body["password"], json!(PROBE_PASSWORD));
+        assert_eq!(body["active"], json!(false));
+    }
+
+    #[test]
+    fn tests_fill_route_and_cors_origin() {
+        assert_eq!(
+            fill_route("/task/{id}", TRAVERSAL_SEGMENT),
+            format!("/task/{}", TRAVERSAL_SEGMENT)
+        );
+        assert!(is_cors_origin_allowed(Some("*")));
+        assert!(is_cors_origin_allowed(Some(PROBE_ORIGIN)));
+        assert!(!is_cors_origin_allowed(Some("http://localhost:3000")));
+        assert!(!is_cors_origin_allowed(None));
+    }
+}
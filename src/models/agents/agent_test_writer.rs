@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+
+use crate::{
+    ai_functions::aifunc_testing::print_backend_integration_tests,
+    helpers::{
+        cli::PrintCommand,
+        general::{extract_code_from_code_block, perfom_ai_call, save_backend_files},
+        web_server::{command_failure_report, test_web_server},
+    },
+    models::agent_basic::{
+        basic_agent::{AgentState, BasicAgent},
+        basic_trait::BasicTraits,
+    },
+};
+
+use super::agent_traits::{FactField, FactSheet, SpecialFunctions};
+
+#[derive(Debug)]
+pub struct AgentTestWriter {
+    attributes: BasicAgent,
+    test_failures: Option<String>,
+}
+
+impl AgentTestWriter {
+    pub fn new() -> Self {
+        let attributes = BasicAgent {
+            objective: "Writes and runs integration tests for the backend webserver".to_string(),
+            position: "Test writer".to_string(),
+            state: AgentState::Discovery,
+            memory: Some(vec![]),
+        };
+
+        Self {
+            attributes,
+            test_failures: None,
+        }
+    }
+
+    pub fn test_failures(&self) -> Option<&String> {
+        self.test_failures.as_ref()
+    }
+
+    async fn call_write_integration_tests(&mut self, factsheet: &mut FactSheet) {
+        let is_user_login_and_logout = factsheet
+            .project_scope
+            .is_some_and(|scope| scope.is_user_login_and_logout);
+
+        let msg_context = format!(
+            "BACKEND_FILES: {} \n API_ENDPOINT_SCHEMA: {:?} \n IS_USER_LOGIN_AND_LOGOUT: {} \n FRAMEWORK: {} \n TESTING_STYLE: {} \n PERSISTENCE: {} \n INSTALLED_LIBRARIES: {} \n",
+            factsheet.backend_listing(),
+            factsheet.api_endpoint_schema,
+            is_user_login_and_logout,
+            factsheet.web_framework,
+            factsheet.web_framework.testing_style(),
+            factsheet.persistence_instructions(),
+            factsheet.installed_libraries()
+        );
+
+        let response = perfom_ai_call(
+            msg_context,
+            &self.attributes.position,
+            get_function_string!(print_backend_integration_tests),
+            print_backend_integration_tests,
+        )
+        .await;
+
+        let Some(files) = factsheet.backend_files.as_mut() else {
+            PrintCommand::Issue.print_agent_msg(
+                self.attributes.position.as_str(),
+                "No backend files to add the integration tests to",
+            );
+            return;
+        };
+
+        files.insert_tests(extract_code_from_code_block(&response));
+        save_backend_files(files).expect("Failed to save backend tests");
+    }
+}
+
+#[async_trait]
+impl SpecialFunctions for AgentTestWriter {
+    fn get_attributes_from_agent(&self) -> &BasicAgent {
+        &self.attributes
+    }
+
+    fn reads(&self) -> &[FactField] {
+        &[
+            FactField::ProjectScope,
+            FactField::BackendFiles,
+            FactField::ApiEndpointSchema,
+        ]
+    }
+
+    fn writes(&self) -> &[FactField] {
+        &[FactField::BackendFiles]
+    }
+
+    fn ai_functions(&self) -> &[&'static str] {
+        &[get_function_string!(print_backend_integration_tests)]
+    }
+
+    async fn execute(
+        &mut self,
+        factsheet: &mut FactSheet,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        while self.attributes.state != AgentState::Finished {
+            match self.attributes.state {
+                AgentState::Discovery => {
+                    self.call_write_integration_tests(factsheet).await;
+                    self.attributes.update_state(AgentState::UnitTesting);
+                }
+
+                AgentState::UnitTesting => {
+                    PrintCommand::UnitTest.print_agent_msg(
+                        self.attributes.position.as_str(),
+                        "Running integration tests with cargo test",
+                    );
+
+                    let output = test_web_server(factsheet.persistence)?;
+
+                    if output.status.success() {
+                        PrintCommand::UnitTest.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Integration tests passed",
+                        );
+                        self.test_failures = None;
+                    } else {
+                        PrintCommand::Issue.print_agent_msg(
+                            self.attributes.position.as_str(),
+                            "Integration tests failed",
+                        );
+                        self.test_failures = Some(command_failure_report(&output));
+                    }
+
+                    self.attributes.update_state(AgentState::Finished);
+                }
+
+                _ => self.attributes.update_state(AgentState::Finished),
+            }
+        }
+
+        Ok(())
+    }
+}
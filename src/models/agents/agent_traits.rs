@@ -1,17 +1,107 @@
-use std::fmt::Debug;
+use std::{
+    fmt::{self, Debug},
+    path::PathBuf,
+};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use crate::models::agent_basic::basic_agent::BasicAgent;
+use crate::models::{
+    agent_basic::basic_agent::BasicAgent,
+    general::{
+        backend_files::BackendFiles,
+        edit_mode::EditMode,
+        framework::WebFramework,
+        persistence::PersistenceBackend,
+        schema::{parse_path_params, HttpMethod, TypeSchema},
+        template::CodeTemplate,
+    },
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FactSheet {
     pub project_description: String,
     pub project_scope: Option<ProjectScope>,
     pub external_urls: Option<Vec<String>>,
-    pub backend_code: Option<String>,
+    pub backend_files: Option<BackendFiles>,
     pub api_endpoint_schema: Option<Vec<RouteObject>>,
+    pub frontend_code: Option<String>,
+    pub devops_artifacts: Option<Vec<String>>,
+    pub data_model: Option<DataModel>,
+    #[serde(default)]
+    pub web_framework: WebFramework,
+    #[serde(default)]
+    pub persistence: PersistenceBackend,
+    #[serde(default)]
+    pub template_dirs: Vec<PathBuf>,
+    #[serde(default)]
+    pub code_template: Option<CodeTemplate>,
+    #[serde(default)]
+    pub edit_mode: EditMode,
+}
+
+impl FactSheet {
+    // Libraries in the generated Cargo.toml: the framework's plus sqlx for a SQL persistence
+    pub fn installed_libraries(&self) -> String {
+        match self.persistence.installed_library() {
+            Some(library) => format!("{}, {}", self.web_framework.installed_libraries(), library),
+            None => self.web_framework.installed_libraries().to_string(),
+        }
+    }
+
+    // Migrations are generated from the data model for the SQL backends
+    pub fn has_migrations(&self) -> bool {
+        self.persistence.sql_dialect().is_some() && self.data_model.is_some()
+    }
+
+    // How the backend stores data, given to the LLM as PERSISTENCE
+    pub fn persistence_instructions(&self) -> String {
+        self.persistence.instructions(self.has_migrations())
+    }
+
+    // Every generated backend file in the delimited format the LLM writes them in
+    pub fn backend_listing(&self) -> String {
+        self.backend_files
+            .as_ref()
+            .map_or_else(|| "None".to_string(), BackendFiles::to_delimited)
+    }
+}
+
+// FactSheet fields agents fill in. Settings such as web_framework come from the command line
+// and are only ever read, so they are not listed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FactField {
+    ProjectDescription,
+    ProjectScope,
+    ExternalUrls,
+    BackendFiles,
+    ApiEndpointSchema,
+    FrontendCode,
+    DevopsArtifacts,
+    DataModel,
+    CodeTemplate,
+}
+
+impl FactSheet {
+    // Takes over one field from a copy an agent worked on
+    pub fn merge_field(&mut self, from: &FactSheet, field: FactField) {
+        match field {
+            FactField::ProjectDescription => {
+                self.project_description = from.project_description.clone()
+            }
+            FactField::ProjectScope => self.project_scope = from.project_scope,
+            FactField::ExternalUrls => self.external_urls = from.external_urls.clone(),
+            FactField::BackendFiles => self.backend_files = from.backend_files.clone(),
+            FactField::ApiEndpointSchema => {
+                self.api_endpoint_schema = from.api_endpoint_schema.clone()
+            }
+            FactField::FrontendCode => self.frontend_code = from.frontend_code.clone(),
+            FactField::DevopsArtifacts => self.devops_artifacts = from.devops_artifacts.clone(),
+            FactField::DataModel => self.data_model = from.data_model.clone(),
+            FactField::CodeTemplate => self.code_template = from.code_template.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
@@ -21,21 +111,185 @@ pub struct ProjectScope {
     pub is_external_urls_required: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DataModel {
+    pub entities: Vec<Entity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Entity {
+    pub name: String,
+    pub fields: Vec<EntityField>,
+    #[serde(default)]
+    pub relations: Vec<EntityRelation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EntityField {
+    pub name: String,
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub is_optional: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    String,
+    Integer,
+    Float,
+    Boolean,
+    DateTime,
+    Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EntityRelation {
+    pub kind: RelationKind,
+    pub target: String,
+    pub via_field: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    OneToOne,
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "RawRouteObject")]
 pub struct RouteObject {
-    pub is_route_dynamic: String,
-    pub method: String,
-    pub request_body: serde_json::Value,
-    pub response: serde_json::Value,
     pub route: String,
+    pub method: HttpMethod,
+    pub is_route_dynamic: bool,
+    pub path_params: Vec<String>,
+    pub request_body: Option<TypeSchema>,
+    pub response: Option<TypeSchema>,
 }
 
+impl RouteObject {
+    pub fn new(
+        method: HttpMethod,
+        route: &str,
+        request_body: Option<TypeSchema>,
+        response: Option<TypeSchema>,
+    ) -> Self {
+        let path_params = parse_path_params(route);
+
+        Self {
+            route: route.to_string(),
+            method,
+            is_route_dynamic: !path_params.is_empty(),
+            path_params,
+            request_body,
+            response,
+        }
+    }
+}
+
+// Accepts both the typed format and what the endpoint extractor prints, where
+// "is_route_dynamic" is "true"/"false" and bodies hold sentinels like "None"
+#[derive(Deserialize)]
+struct RawRouteObject {
+    route: String,
+    method: String,
+    #[serde(default)]
+    request_body: serde_json::Value,
+    #[serde(default)]
+    response: serde_json::Value,
+}
+
+impl TryFrom<RawRouteObject> for RouteObject {
+    type Error = String;
+
+    fn try_from(raw: RawRouteObject) -> Result<Self, Self::Error> {
+        Ok(Self::new(
+            raw.method.parse()?,
+            &raw.route,
+            TypeSchema::from_llm_value(&raw.request_body),
+            TypeSchema::from_llm_value(&raw.response),
+        ))
+    }
+}
+
+// Failures the managing agent can re-plan around by changing the scope, the external urls or
+// the code template. Agents return them as their error
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplanTrigger {
+    // The errors of the last build or test run
+    BugFixesExhausted(String),
+    // Every url the architect found, none of which answered with 200
+    ExternalUrlsDead(Vec<String>),
+}
+
+impl fmt::Display for ReplanTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BugFixesExhausted(_) => write!(f, "Backend bug fix attempts exhausted"),
+            Self::ExternalUrlsDead(urls) => {
+                write!(f, "Every external url is dead: {}", urls.join(", "))
+            }
+        }
+    }
+}
+
+impl ReplanTrigger {
+    // Whether the agent that failed runs again after the re-plan. Dead urls are replaced by
+    // the re-plan itself, while the backend has to be written again
+    pub fn reruns_agent(&self) -> bool {
+        matches!(self, Self::BugFixesExhausted(_))
+    }
+}
+
+impl std::error::Error for ReplanTrigger {}
+
 #[async_trait]
-pub trait SpecialFunctions: Debug {
+pub trait SpecialFunctions: Debug + Send {
     fn get_attributes_from_agent(&self) -> &BasicAgent;
 
+    // The FactSheet fields execute looks at and the ones it changes. The managing agent runs
+    // agents whose fields do not overlap at the same time
+    fn reads(&self) -> &[FactField];
+
+    fn writes(&self) -> &[FactField];
+
+    // Every ai_function execute calls, the ones a pipeline file may set a model for
+    fn ai_functions(&self) -> &[&'static str];
+
     async fn execute(
         &mut self,
         factsheet: &mut FactSheet,
     ) -> Result<(), Box<dyn std::error::Error>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_route_object_from_llm_format() {
+        let llm_output = r#"[
+            {"route": "/item/{id}", "is_route_dynamic": "true", "method": "get", "request_body": "None", "response": {"id": "number", "name": "string"}},
+            {"route": "/crypto", "is_route_dynamic": "false", "method": "GET", "request_body": "None", "response": "not_provided"}
+        ]"#;
+
+        let routes: Vec<RouteObject> =
+            serde_json::from_str(llm_output).expect("Failed to decode llm routes");
+
+        assert_eq!(routes[0].method, HttpMethod::Get);
+        assert!(routes[0].is_route_dynamic);
+        assert_eq!(routes[0].path_params, vec!["id"]);
+        assert_eq!(routes[0].request_body, None);
+        assert!(matches!(routes[0].response, Some(TypeSchema::Object(_))));
+        assert!(!routes[1].is_route_dynamic);
+        assert_eq!(routes[1].response, Some(TypeSchema::Unknown));
+
+        let typed = serde_json::to_string(&routes).expect("Failed to encode routes");
+        let decoded: Vec<RouteObject> =
+            serde_json::from_str(&typed).expect("Failed to decode typed routes");
+        assert_eq!(decoded, routes);
+    }
+}
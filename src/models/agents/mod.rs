@@ -1,3 +1,10 @@
 pub mod agent_architect;
 pub mod agent_backend;
+pub mod agent_code_reviewer;
+pub mod agent_data_model;
+pub mod agent_devops;
+pub mod agent_frontend;
+pub mod agent_plugin;
+pub mod agent_security_auditor;
+pub mod agent_test_writer;
 pub mod agent_traits;
@@ -0,0 +1,140 @@
+use crate::models::agents::agent_traits::FactField;
+
+// The FactSheet fields an agent reads and the ones it writes
+pub type FieldAccess<'a> = (&'a [FactField], &'a [FactField]);
+
+// Groups agents, given in the order they were added, into waves that run one after another.
+// An agent waits for every earlier agent that writes a field it uses or reads a field it
+// writes, so agents in the same wave share nothing and can run at the same time
+pub fn plan_waves(accesses: &[FieldAccess]) -> Vec<Vec<usize>> {
+    let mut levels: Vec<usize> = vec![];
+
+    for (index, access) in accesses.iter().enumerate() {
+        let level = accesses[..index]
+            .iter()
+            .zip(&levels)
+            .filter(|(earlier, _)| depends_on(access, earlier))
+            .map(|(_, level)| level + 1)
+            .max()
+            .unwrap_or(0);
+        levels.push(level);
+    }
+
+    let mut waves = vec![vec![]; levels.iter().max().map_or(0, |level| level + 1)];
+    for (index, level) in levels.into_iter().enumerate() {
+        waves[level].push(index);
+    }
+
+    waves
+}
+
+// Picks the agents to run again once a re-plan changed some FactSheet fields: the agents given,
+// every agent reading a changed field and every agent reading what an earlier pick writes
+pub fn plan_rerun(accesses: &[FieldAccess], seeds: &[usize], changed: &[FactField]) -> Vec<usize> {
+    let mut changed = changed.to_vec();
+    let mut rerun = vec![];
+
+    for (index, (reads, writes)) in accesses.iter().enumerate() {
+        if seeds.contains(&index) || reads.iter().any(|field| changed.contains(field)) {
+            rerun.push(index);
+            changed.extend_from_slice(writes);
+        }
+    }
+
+    rerun
+}
+
+fn depends_on(
+    (reads, writes): &FieldAccess,
+    (earlier_reads, earlier_writes): &FieldAccess,
+) -> bool {
+    earlier_writes
+        .iter()
+        .any(|field| reads.contains(field) || writes.contains(field))
+        || earlier_reads.iter().any(|field| writes.contains(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::agents::{
+        agent_architect::AgentSolutionArchitect, agent_backend::AgentBackendDeveloper,
+        agent_data_model::AgentDataModelDesigner, agent_devops::AgentDevOps,
+        agent_frontend::AgentFrontendDeveloper, agent_security_auditor::AgentSecurityAuditor,
+        agent_traits::SpecialFunctions,
+    };
+
+    #[test]
+    fn tests_plan_waves() {
+        let agents: Vec<Box<dyn SpecialFunctions>> = vec![
+            Box::new(AgentSolutionArchitect::new()),
+            Box::new(AgentDataModelDesigner::new()),
+            Box::new(AgentBackendDeveloper::new()),
+            Box::new(AgentSecurityAuditor::new()),
+            Box::new(AgentDevOps::new()),
+            Box::new(AgentFrontendDeveloper::new()),
+        ];
+        let accesses = agents
+            .iter()
+            .map(|agent| (agent.reads(), agent.writes()))
+            .collect::<Vec<_>>();
+
+        // Once the backend exists, the audit, devops and frontend only read what it wrote
+        assert_eq!(
+            plan_waves(&accesses),
+            vec![vec![0], vec![1], vec![2], vec![3, 4, 5]]
+        );
+
+        // Without the backend in between, the audit still waits for the scope it checks
+        assert_eq!(
+            plan_waves(&[accesses[0], accesses[3]]),
+            vec![vec![0], vec![1]]
+        );
+
+        // A reader waits for the writer before it, and a writer for the reader before it
+        let description = &[FactField::ProjectDescription][..];
+        let frontend = &[FactField::FrontendCode][..];
+        assert_eq!(
+            plan_waves(&[
+                (description, frontend),
+                (frontend, &[]),
+                (description, &[]),
+                (&[], description),
+            ]),
+            vec![vec![0, 2], vec![1, 3]]
+        );
+        assert!(plan_waves(&[]).is_empty());
+    }
+
+    #[test]
+    fn tests_plan_rerun() {
+        let agents: Vec<Box<dyn SpecialFunctions>> = vec![
+            Box::new(AgentSolutionArchitect::new()),
+            Box::new(AgentDataModelDesigner::new()),
+            Box::new(AgentBackendDeveloper::new()),
+            Box::new(AgentSecurityAuditor::new()),
+            Box::new(AgentDevOps::new()),
+            Box::new(AgentFrontendDeveloper::new()),
+        ];
+        let accesses = agents
+            .iter()
+            .map(|agent| (agent.reads(), agent.writes()))
+            .collect::<Vec<_>>();
+
+        // A new template only concerns the backend that failed and what was built on it
+        assert_eq!(
+            plan_rerun(&accesses, &[2], &[FactField::CodeTemplate]),
+            vec![2, 3, 4, 5]
+        );
+        // A simpler scope is designed again from the data model on
+        assert_eq!(
+            plan_rerun(&accesses, &[2], &[FactField::ProjectScope]),
+            vec![1, 2, 3, 4, 5]
+        );
+        // Other urls leave the architect and the data model as they were
+        assert_eq!(
+            plan_rerun(&accesses, &[], &[FactField::ExternalUrls]),
+            vec![2, 3, 4, 5]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Deserialize;
+
+use crate::models::agents::{
+    agent_architect::AgentSolutionArchitect,
+    agent_backend::AgentBackendDeveloper,
+    agent_data_model::AgentDataModelDesigner,
+    agent_devops::AgentDevOps,
+    agent_frontend::AgentFrontendDeveloper,
+    agent_plugin::{AgentPlugin, Plugin},
+    agent_security_auditor::AgentSecurityAuditor,
+    agent_traits::SpecialFunctions,
+};
+
+pub type AgentFactory = Arc<dyn Fn() -> Box<dyn SpecialFunctions> + Send + Sync>;
+type BuiltinFactory = fn() -> Box<dyn SpecialFunctions>;
+
+// An agent a pipeline can run. Without a pipeline file, registered agents run in position order
+pub struct AgentRegistration {
+    pub name: String,
+    pub description: String,
+    pub position: u32,
+    factory: AgentFactory,
+}
+
+#[derive(Default)]
+pub struct AgentRegistry {
+    registrations: Vec<AgentRegistration>,
+}
+
+impl AgentRegistry {
+    pub fn with_builtin_agents() -> Self {
+        let mut registry = Self::default();
+        let builtin: [(&str, &str, u32, BuiltinFactory); 6] = [
+            (
+                "architect",
+                "Scopes the project and finds external APIs",
+                10,
+                || Box::new(AgentSolutionArchitect::new()),
+            ),
+            (
+                "data_model",
+                "Designs the entities and their storage",
+                20,
+                || Box::new(AgentDataModelDesigner::new()),
+            ),
+            ("backend", "Writes, tests and fixes the backend", 30, || {
+                Box::new(AgentBackendDeveloper::new())
+            }),
+            (
+                "security_auditor",
+                "Audits the backend for vulnerabilities",
+                40,
+                || Box::new(AgentSecurityAuditor::new()),
+            ),
+            (
+                "devops",
+                "Packages the backend with Docker, compose and make",
+                50,
+                || Box::new(AgentDevOps::new()),
+            ),
+            ("frontend", "Builds a page on top of the API", 60, || {
+                Box::new(AgentFrontendDeveloper::new())
+            }),
+        ];
+
+        for (name, description, position, factory) in builtin {
+            registry
+                .register(name, description, position, factory)
+                .expect("Built-in agent names are unique");
+        }
+
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        name: &str,
+        description: &str,
+        position: u32,
+        factory: impl Fn() -> Box<dyn SpecialFunctions> + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        if self.get(name).is_some() {
+            return Err(format!("Agent {} is already registered", name));
+        }
+
+        // Keeps position order, agents sharing a position stay in the order they registered
+        let index = self
+            .registrations
+            .partition_point(|registration| registration.position <= position);
+        self.registrations.insert(
+            index,
+            AgentRegistration {
+                name: name.to_string(),
+                description: description.to_string(),
+                position,
+                factory: Arc::new(factory),
+            },
+        );
+
+        Ok(())
+    }
+
+    pub fn register_plugin(&mut self, plugin: Plugin) -> Result<(), String> {
+        let manifest = plugin.manifest().clone();
+        let plugin = Arc::new(plugin);
+
+        self.register(
+            &manifest.name,
+            &manifest.description,
+            manifest.position,
+            move || Box::new(AgentPlugin::new(plugin.clone())),
+        )
+    }
+
+    pub fn registrations(&self) -> &[AgentRegistration] {
+        &self.registrations
+    }
+
+    fn get(&self, name: &str) -> Option<&AgentRegistration> {
+        self.registrations
+            .iter()
+            .find(|registration| registration.name == name)
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn SpecialFunctions>> {
+        self.get(name).map(|registration| (registration.factory)())
+    }
+
+    pub fn factory(&self, name: &str) -> Option<AgentFactory> {
+        self.get(name)
+            .map(|registration| registration.factory.clone())
+    }
+}
+
+// agents.toml, the lineup format from before pipeline files. It still loads, as a pipeline
+// with a single build stage:
+//   agents = ["architect", "backend", "license_checker"]
+//
+//   [[plugins]]
+//   path = "plugins/liblicense_checker.so"
+// Leaving out agents runs every registered agent, plugins included, by position
+#[derive(Debug, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AgentsConfig {
+    pub agents: Option<Vec<String>>,
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PluginConfig {
+    pub path: PathBuf,
+}
+
+impl AgentsConfig {
+    // Plugin paths are relative to the config file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+        let mut config: Self = toml::from_str(&content)
+            .map_err(|error| format!("Invalid {}: {}", path.display(), error))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for plugin in &mut config.plugins {
+            plugin.path = base.join(&plugin.path);
+        }
+
+        Ok(config)
+    }
+
+    // The agents it runs, every registered one when it does not list them
+    pub fn lineup(&self, registry: &AgentRegistry) -> Vec<String> {
+        self.agents.clone().unwrap_or_else(|| {
+            registry
+                .registrations()
+                .iter()
+                .map(|registration| registration.name.clone())
+                .collect()
+        })
+    }
+}
+
+// The built-in agents plus the plugins agents.toml loads
+pub fn load_registry(config_path: Option<&Path>) -> Result<(AgentRegistry, AgentsConfig), String> {
+    let config = match config_path {
+        Some(path) => AgentsConfig::load(path)?,
+        None => AgentsConfig::default(),
+    };
+
+    let mut registry = AgentRegistry::with_builtin_agents();
+    for plugin in &config.plugins {
+        registry.register_plugin(Plugin::load(&plugin.path)?)?;
+    }
+
+    Ok((registry, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_agent_registry() {
+        let mut registry = AgentRegistry::with_builtin_agents();
+        registry
+            .register(
+                "reviewer",
+                "Reviews the backend before the audit",
+                35,
+                || Box::new(AgentSecurityAuditor::new()),
+            )
+            .expect("Failed to register agent");
+
+        assert_eq!(
+            registry
+                .registrations()
+                .iter()
+                .map(|registration| registration.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "architect",
+                "data_model",
+                "backend",
+                "reviewer",
+                "security_auditor",
+                "devops",
+                "frontend"
+            ]
+        );
+        assert!(registry
+            .register("backend", "Another backend", 90, || Box::new(
+                AgentBackendDeveloper::new()
+            ))
+            .is_err());
+
+        let backend = registry.create("backend").expect("Failed to create agent");
+        assert_eq!(
+            backend.get_attributes_from_agent().position,
+            "Backend developer"
+        );
+        assert!(registry.create("tester").is_none());
+
+        let config: AgentsConfig =
+            toml::from_str("agents = [\"backend\", \"architect\"]\n").expect("Failed to parse");
+        assert_eq!(config.lineup(&registry), vec!["backend", "architect"]);
+        assert_eq!(AgentsConfig::default().lineup(&registry).len(), 7);
+        assert!(toml::from_str::<AgentsConfig>("agent = [\"backend\"]\n").is_err());
+    }
+}
@@ -1,23 +1,138 @@
+use std::{
+    panic,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use reqwest::Client;
+use serde::Deserialize;
+
 use crate::{
-    ai_functions::aifunc_managing::convert_user_input_to_goal,
-    helpers::general::perfom_ai_call,
+    ai_functions::aifunc_managing::{
+        convert_amend_request_to_goal, convert_user_input_to_goal, print_imported_project_summary,
+        print_replan_decision,
+    },
+    apis::call_request::with_model_config,
+    exporters::openapi::save_openapi_spec,
+    helpers::{
+        cli::{buffer_agent_msgs, get_user_response, print_agent_msgs, PrintCommand, RunOptions},
+        general::{
+            check_status_code, perfom_ai_call, perfom_ai_call_decoded, save_api_endpoints,
+            save_backend_files,
+        },
+        history::{commit_backend_iteration, load_factsheet},
+        importer::{detect_framework, detect_persistence, read_crate_files},
+        route_extractor::extract_routes_from_sources,
+        templates::TemplateRegistry,
+    },
     models::{
         agent_basic::basic_agent::{AgentState, BasicAgent},
         agents::{
             agent_architect::AgentSolutionArchitect,
-            agent_traits::{FactSheet, SpecialFunctions},
+            agent_backend::AgentBackendDeveloper,
+            agent_test_writer::AgentTestWriter,
+            agent_traits::{FactField, FactSheet, ProjectScope, ReplanTrigger, SpecialFunctions},
+        },
+        agents_manager::{
+            agent_graph::{plan_rerun, plan_waves},
+            pipeline::{load_pipeline, AgentStep, OnFailure, Stage, DEFAULT_PIPELINE_FILE},
+        },
+        general::{
+            backend_files::MANIFEST_FILE, edit_mode::EditMode, llm::ModelConfig,
+            template::TemplateKind,
         },
     },
 };
 
+const MAX_REPLANS: usize = 2;
+
+// What a follow-up request changes about a project that was already generated
+#[derive(Debug, Deserialize)]
+struct AmendGoal {
+    change_goal: String,
+    project_description: String,
+    is_scope_change_required: bool,
+}
+
+// What the LLM makes of a crate that was written outside rustptty
+#[derive(Debug, Deserialize)]
+struct ImportSummary {
+    project_description: String,
+    project_scope: ProjectScope,
+}
+
+// How the LLM changes the plan after agents gave up
+#[derive(Debug, Deserialize)]
+struct ReplanDecision {
+    reason: String,
+    #[serde(flatten)]
+    action: ReplanAction,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ReplanAction {
+    SimplifyScope { project_scope: ProjectScope },
+    SwapExternalUrls { external_urls: Vec<String> },
+    ChangeTemplate { template: TemplateKind },
+    GiveUp,
+}
+
+impl ReplanAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SimplifyScope { .. } => "simplify_scope",
+            Self::SwapExternalUrls { .. } => "swap_external_urls",
+            Self::ChangeTemplate { .. } => "change_template",
+            Self::GiveUp => "give_up",
+        }
+    }
+}
+
+// An agent's error, with the re-plan it asks for when it is one
+#[derive(Debug, PartialEq)]
+struct StepError {
+    message: String,
+    trigger: Option<ReplanTrigger>,
+}
+
+impl From<Box<dyn std::error::Error>> for StepError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        Self {
+            message: err.to_string(),
+            trigger: err.downcast_ref::<ReplanTrigger>().cloned(),
+        }
+    }
+}
+
+// An agent that stopped with an error, by its index among the steps that ran
+#[derive(Debug)]
+struct StepFailure {
+    index: usize,
+    position: String,
+    error: StepError,
+    stops: bool,
+}
+
+// The steps a call to execute_steps ran, in the order they were given, and the ones that
+// never started because an agent set to stop on failure failed or a re-plan is due
+struct StepsRun {
+    steps: Vec<AgentStep>,
+    failures: Vec<StepFailure>,
+    pending: Vec<usize>,
+}
+
 pub struct ManagingAgent {
     attributes: BasicAgent,
     factsheet: FactSheet,
-    agents: Vec<Box<dyn SpecialFunctions>>,
+    stages: Vec<Stage>,
 }
 
 impl ManagingAgent {
-    pub async fn new(user_req: String) -> Result<Self, Box<dyn std::error::Error>> {
+    pub async fn new(
+        user_req: String,
+        options: RunOptions,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let position = "Project Manager".to_string();
 
         let attributes = BasicAgent {
@@ -28,61 +143,611 @@ impl ManagingAgent {
             memory: None,
         };
 
-        let project_description = perfom_ai_call(
-            user_req,
-            &position,
-            get_function_string!(convert_user_input_to_goal),
-            convert_user_input_to_goal,
+        // A broken pipeline file or plugin stops the run before anything is generated
+        let pipeline = options
+            .pipeline
+            .or_else(|| Some(PathBuf::from(DEFAULT_PIPELINE_FILE)).filter(|path| path.is_file()));
+        let pipeline = load_pipeline(pipeline.as_deref())?;
+        for warning in &pipeline.warnings {
+            PrintCommand::Issue.print_agent_msg(&position, warning);
+        }
+        let model = ModelConfig {
+            defaults: pipeline.defaults,
+            ..ModelConfig::default()
+        };
+
+        let project_description = with_model_config(
+            model,
+            perfom_ai_call(
+                user_req,
+                &position,
+                get_function_string!(convert_user_input_to_goal),
+                convert_user_input_to_goal,
+            ),
         )
         .await;
 
-        let agents: Vec<Box<dyn SpecialFunctions>> = vec![];
-
         let factsheet = FactSheet {
             project_description,
             project_scope: None,
             external_urls: None,
-            backend_code: None,
+            backend_files: None,
             api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: options.framework,
+            persistence: options.persistence,
+            template_dirs: options.template_dirs,
+            code_template: None,
+            edit_mode: options.edit_mode,
+        };
+
+        Ok(Self {
+            attributes,
+            factsheet,
+            stages: pipeline.stages,
+        })
+    }
+
+    // Picks up the FactSheet and code of the last run and sets the agents up to change it
+    // rather than start over. The scope is only worked out again when the change needs it
+    pub async fn amend(user_req: String) -> Result<Self, Box<dyn std::error::Error>> {
+        let position = "Project Manager".to_string();
+
+        let attributes = BasicAgent {
+            objective: "Manage agents who are changing a website already built for the user"
+                .to_string(),
+            position: position.clone(),
+            state: AgentState::Discovery,
+            memory: None,
+        };
+
+        let mut factsheet = load_factsheet()?;
+        if factsheet.backend_files.is_none() {
+            return Err("The saved project has no backend code to amend".into());
+        }
+
+        let msg_context = format!(
+            "PROJECT_DESCRIPTION: {} \n API_ENDPOINT_SCHEMA: {:?} \n USER_REQUEST: {}",
+            factsheet.project_description, factsheet.api_endpoint_schema, user_req
+        );
+        let amend_goal = perfom_ai_call_decoded::<AmendGoal>(
+            msg_context,
+            &position,
+            get_function_string!(convert_amend_request_to_goal),
+            convert_amend_request_to_goal,
+        )
+        .await;
+
+        factsheet.project_description = amend_goal.project_description;
+
+        let mut agents: Vec<Box<dyn SpecialFunctions>> = vec![];
+        if amend_goal.is_scope_change_required {
+            agents.push(Box::new(AgentSolutionArchitect::new()));
+        }
+        agents.push(Box::new(AgentBackendDeveloper::amending(
+            amend_goal.change_goal,
+            &factsheet,
+        )));
+
+        Ok(Self {
+            attributes,
+            factsheet,
+            stages: vec![Stage::new("amend", agents)],
+        })
+    }
+
+    // Rebuilds a FactSheet from an existing crate: routes from the static extractor, description
+    // and scope from an LLM summary. The crate becomes the workspace, ready for amend or tests
+    pub async fn import(crate_path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let position = "Project Manager".to_string();
+
+        let attributes = BasicAgent {
+            objective: "Manage agents who are working on a website the user already has"
+                .to_string(),
+            position: position.clone(),
+            state: AgentState::Discovery,
+            memory: None,
+        };
+
+        let files = read_crate_files(crate_path)?;
+        let manifest = files.get(MANIFEST_FILE).unwrap_or_default();
+        let web_framework = detect_framework(manifest)
+            .ok_or("Cargo.toml depends on none of actix-web, axum or rocket")?;
+        let persistence = detect_persistence(manifest);
+        let routes = extract_routes_from_sources(files.rust_sources(), web_framework)?;
+
+        let msg_context = format!(
+            "FRAMEWORK: {} \n API_ENDPOINT_SCHEMA: {:?} \n CODE_INPUT: {}",
+            web_framework,
+            routes,
+            files.to_delimited()
+        );
+        let summary = perfom_ai_call_decoded::<ImportSummary>(
+            msg_context,
+            &position,
+            get_function_string!(print_imported_project_summary),
+            print_imported_project_summary,
+        )
+        .await;
+
+        let factsheet = FactSheet {
+            project_description: summary.project_description,
+            project_scope: Some(summary.project_scope),
+            external_urls: None,
+            backend_files: Some(files),
+            api_endpoint_schema: Some(routes),
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework,
+            persistence,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::default(),
         };
 
+        if let Some(files) = &factsheet.backend_files {
+            save_backend_files(files)?;
+        }
+        if let Some(routes) = &factsheet.api_endpoint_schema {
+            save_api_endpoints(routes)?;
+        }
+        save_openapi_spec(&factsheet)?;
+
+        let message = format!("{}: imported {}", position, crate_path.display());
+        commit_backend_iteration(&factsheet, &message)?;
+
+        Ok(Self {
+            attributes,
+            factsheet,
+            stages: vec![],
+        })
+    }
+
+    // Writes and runs integration tests for the saved project, generated or imported
+    pub fn testing() -> Result<Self, Box<dyn std::error::Error>> {
+        let attributes = BasicAgent {
+            objective: "Manage agents who are testing a website already built for the user"
+                .to_string(),
+            position: "Project Manager".to_string(),
+            state: AgentState::Discovery,
+            memory: None,
+        };
+
+        let factsheet = load_factsheet()?;
+        if factsheet.api_endpoint_schema.is_none() {
+            return Err("The saved project has no endpoints to write tests for".into());
+        }
+
         Ok(Self {
             attributes,
             factsheet,
-            agents,
+            stages: vec![Stage::new("tests", vec![Box::new(AgentTestWriter::new())])],
         })
     }
 
-    fn add_agent(&mut self, agent: Box<dyn SpecialFunctions>) {
-        self.agents.push(agent);
+    pub fn factsheet(&self) -> &FactSheet {
+        &self.factsheet
     }
 
-    fn create_agents(&mut self) {
-        self.add_agent(Box::new(AgentSolutionArchitect::new()));
-        // ! TODO Add Backend Agent
+    // Records the state of the project after agents ran outside the backend developer
+    pub fn commit_iteration(&self, message: &str) -> std::io::Result<()> {
+        commit_backend_iteration(&self.factsheet, message)
     }
 
     pub async fn execute_project(&mut self) {
-        self.create_agents();
+        self.execute_agents().await;
+    }
+
+    // Runs the stages set up by new, amend or testing in order. A stage that needs approval
+    // asks the user first, and agents whose condition does not hold when their stage starts
+    // are skipped. When the backend runs out of bug fixes or every external url is dead, the
+    // agents after it wait while the plan is changed, then the agents it affects run, up to
+    // MAX_REPLANS times in all. The pipeline stops after a stage where an agent set to stop on
+    // failure still failed
+    pub async fn execute_agents(&mut self) {
+        let position = self.attributes.position.clone();
+        // Every step that ran so far, a re-plan may run any of them again
+        let mut ran: Vec<AgentStep> = vec![];
+        let mut replans: Vec<String> = vec![];
+
+        for stage in std::mem::take(&mut self.stages) {
+            if stage.approval && !approve_stage(&stage.name) {
+                let issue = format!(
+                    "Stopped before the {} stage, it was not approved",
+                    stage.name
+                );
+                PrintCommand::Issue.print_agent_msg(&position, &issue);
+                break;
+            }
+
+            let (steps, skipped): (Vec<_>, Vec<_>) = stage.steps.into_iter().partition(|step| {
+                step.when
+                    .is_none_or(|condition| condition.is_met(&self.factsheet))
+            });
+            for step in &skipped {
+                print_skipped_step(&position, step);
+            }
+
+            // Amend and testing set agents up by hand, there is no way to start them again
+            let can_replan = ran.iter().chain(&steps).all(|step| step.factory.is_some());
+
+            let offset = ran.len();
+            let run = self
+                .execute_steps(steps, can_replan && replans.len() < MAX_REPLANS)
+                .await;
+            ran.extend(run.steps);
+            let mut failures = run.failures;
+            for failure in &mut failures {
+                failure.index += offset;
+            }
+            let mut pending = run
+                .pending
+                .into_iter()
+                .map(|index| index + offset)
+                .collect::<Vec<_>>();
+
+            while can_replan
+                && replans.len() < MAX_REPLANS
+                && failures
+                    .iter()
+                    .any(|failure| failure.error.trigger.is_some())
+            {
+                let Some(changed) = self.replan(&failures, &mut replans).await else {
+                    break;
+                };
+                if changed.is_empty() {
+                    continue;
+                }
+
+                // The re-plan stands in for what an agent without a rerun would have written
+                failures.retain(|failure| {
+                    failure
+                        .error
+                        .trigger
+                        .as_ref()
+                        .is_none_or(ReplanTrigger::reruns_agent)
+                });
+                let mut seeds = failures
+                    .iter()
+                    .filter(|failure| failure.error.trigger.is_some())
+                    .map(|failure| failure.index)
+                    .collect::<Vec<_>>();
+                seeds.append(&mut pending);
+
+                let accesses = ran
+                    .iter()
+                    .map(|step| (step.agent.reads(), step.agent.writes()))
+                    .collect::<Vec<_>>();
+                let rerun = plan_rerun(&accesses, &seeds, &changed);
+                pending = self
+                    .rerun_steps(&mut ran, &rerun, &mut failures, replans.len() < MAX_REPLANS)
+                    .await;
+            }
+
+            // Agents held back for a re-plan that did not happen run on what there is
+            if !pending.is_empty() && !failures.iter().any(|failure| failure.stops) {
+                let held = std::mem::take(&mut pending);
+                self.rerun_steps(&mut ran, &held, &mut failures, false)
+                    .await;
+            }
+
+            if failures.iter().any(|failure| failure.stops) {
+                let issue = format!("Stopped after the {} stage, an agent failed", stage.name);
+                PrintCommand::Issue.print_agent_msg(&position, &issue);
+                break;
+            }
+        }
+    }
+
+    // Runs the agents of a stage in waves. Agents in a wave each work on their own copy of the
+    // FactSheet and the fields they write are merged back in the order the agents were listed,
+    // which is also the order their messages are printed in. With hold_on_trigger, the waves
+    // after an agent asked for a re-plan are left for after it
+    async fn execute_steps(&mut self, steps: Vec<AgentStep>, hold_on_trigger: bool) -> StepsRun {
+        let accesses = steps
+            .iter()
+            .map(|step| (step.agent.reads(), step.agent.writes()))
+            .collect::<Vec<_>>();
+        let waves = plan_waves(&accesses);
+
+        let mut steps = steps.into_iter().map(Some).collect::<Vec<_>>();
+        let mut failures = vec![];
+        let mut should_continue = true;
+
+        let mut waves = waves.into_iter();
+        for wave in waves.by_ref() {
+            let is_buffered = wave.len() > 1;
+
+            let mut tasks = vec![];
+            for &index in &wave {
+                let step = steps[index].take().expect("Agent scheduled twice");
+                let factsheet = self.factsheet.clone();
+
+                tasks.push(tokio::spawn(async move {
+                    let execution = run_step(step, factsheet);
+                    let ((step, factsheet, agent_res), msgs) = if is_buffered {
+                        buffer_agent_msgs(execution).await
+                    } else {
+                        (execution.await, vec![])
+                    };
+
+                    (index, step, factsheet, agent_res, msgs)
+                }));
+            }
+
+            for task in tasks {
+                let (index, step, factsheet, agent_res, msgs) = match task.await {
+                    Ok(finished) => finished,
+                    Err(err) => panic::resume_unwind(err.into_panic()),
+                };
 
-        for agent in &mut self.agents {
-            let agent_res = agent.execute(&mut self.factsheet).await;
+                print_agent_msgs(&msgs);
+                for &field in step.agent.writes() {
+                    self.factsheet.merge_field(&factsheet, field);
+                }
 
-            let agent_info = agent.get_attributes_from_agent();
-            dbg!(agent_info);
+                let agent_info = step.agent.get_attributes_from_agent();
+                if let Err(error) = agent_res {
+                    let issue = format!("Agent stopped with an error: {}", error.message);
+                    PrintCommand::Issue.print_agent_msg(agent_info.position.as_str(), &issue);
+
+                    let stops = step.on_failure == OnFailure::Stop;
+                    should_continue &= !stops;
+                    failures.push(StepFailure {
+                        index,
+                        position: agent_info.position.clone(),
+                        error,
+                        stops,
+                    });
+                }
+                dbg!(agent_info);
+                steps[index] = Some(step);
+            }
+
+            let is_replan_due = failures
+                .iter()
+                .any(|failure| failure.error.trigger.is_some());
+            if !should_continue || (hold_on_trigger && is_replan_due) {
+                break;
+            }
+        }
+
+        StepsRun {
+            steps: steps
+                .into_iter()
+                .map(|step| step.expect("Agent did not come back"))
+                .collect(),
+            failures,
+            pending: waves.flatten().collect(),
         }
     }
+
+    // Starts the steps at indices in ran again with fresh agents, leaving out the ones whose
+    // condition no longer holds, and keeps their outcome in place of the earlier one. Returns
+    // the indices of the steps held back for a re-plan
+    async fn rerun_steps(
+        &mut self,
+        ran: &mut [AgentStep],
+        indices: &[usize],
+        failures: &mut Vec<StepFailure>,
+        hold_on_trigger: bool,
+    ) -> Vec<usize> {
+        let position = self.attributes.position.clone();
+
+        // The re-plan may have changed what the conditions look at
+        let (rerun, skipped): (Vec<usize>, Vec<usize>) = indices.iter().partition(|&&index| {
+            ran[index]
+                .when
+                .is_none_or(|condition| condition.is_met(&self.factsheet))
+        });
+        for &index in &skipped {
+            print_skipped_step(&position, &ran[index]);
+        }
+
+        let steps = rerun
+            .iter()
+            .map(|&index| ran[index].restart().expect("Every step has a factory"))
+            .collect();
+        let run = self.execute_steps(steps, hold_on_trigger).await;
+
+        for (&index, step) in rerun.iter().zip(run.steps) {
+            ran[index] = step;
+        }
+        failures.retain(|failure| !indices.contains(&failure.index));
+        for mut failure in run.failures {
+            failure.index = rerun[failure.index];
+            failures.push(failure);
+        }
+
+        run.pending.into_iter().map(|index| rerun[index]).collect()
+    }
+
+    // Asks the LLM how to change the plan around the failures and applies its answer. Returns
+    // the FactSheet fields that changed, empty when the answer could not be applied, or None
+    // when the LLM gives up
+    async fn replan(
+        &mut self,
+        failures: &[StepFailure],
+        previous: &mut Vec<String>,
+    ) -> Option<Vec<FactField>> {
+        let position = self.attributes.position.clone();
+
+        let failure_report = failures
+            .iter()
+            .filter_map(|failure| match &failure.error.trigger {
+                Some(ReplanTrigger::BugFixesExhausted(errors)) => Some(format!(
+                    "{}: {}, the last errors were: {}",
+                    failure.position, failure.error.message, errors
+                )),
+                Some(_) => Some(format!("{}: {}", failure.position, failure.error.message)),
+                None => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let msg_context = format!(
+            "FAILURE_REPORT: {} \n PROJECT_DESCRIPTION: {} \n PROJECT_SCOPE: {:?} \n EXTERNAL_URLS: {:?} \n FRAMEWORK: {} \n CODE_TEMPLATE: {:?} \n PREVIOUS_REPLANS: {:?}",
+            failure_report,
+            self.factsheet.project_description,
+            self.factsheet.project_scope,
+            self.factsheet.external_urls,
+            self.factsheet.web_framework,
+            self.factsheet.code_template.as_ref().map(|template| template.kind),
+            previous
+        );
+        let decision = perfom_ai_call_decoded::<ReplanDecision>(
+            msg_context,
+            &position,
+            get_function_string!(print_replan_decision),
+            print_replan_decision,
+        )
+        .await;
+
+        let replan = format!("{}: {}", decision.action.as_str(), decision.reason);
+        PrintCommand::AICall.print_agent_msg(&position, &format!("Re-planning, {}", replan));
+        previous.push(replan);
+
+        let action = match decision.action {
+            ReplanAction::GiveUp => return None,
+            ReplanAction::SwapExternalUrls { external_urls } => ReplanAction::SwapExternalUrls {
+                external_urls: live_urls(external_urls).await,
+            },
+            action => action,
+        };
+
+        match apply_replan(&mut self.factsheet, action) {
+            Ok(changed) => Some(changed),
+            Err(err) => {
+                let issue = format!("Failed to re-plan: {}", err);
+                PrintCommand::Issue.print_agent_msg(&position, &issue);
+                Some(vec![])
+            }
+        }
+    }
+}
+
+// Runs the agent with its model config. Each retry starts a fresh agent from the FactSheet
+// the stage gave it
+async fn run_step(
+    mut step: AgentStep,
+    snapshot: FactSheet,
+) -> (AgentStep, FactSheet, Result<(), StepError>) {
+    let mut attempt = 0;
+
+    loop {
+        let mut factsheet = snapshot.clone();
+        let agent_res = with_model_config(step.model.clone(), step.agent.execute(&mut factsheet))
+            .await
+            .map_err(StepError::from);
+
+        match (
+            agent_res,
+            step.factory.as_ref().filter(|_| attempt < step.retries),
+        ) {
+            (Err(err), Some(factory)) => {
+                attempt += 1;
+                let issue = format!(
+                    "Agent failed: {}, retrying ({} of {})",
+                    err.message, attempt, step.retries
+                );
+                PrintCommand::Issue
+                    .print_agent_msg(&step.agent.get_attributes_from_agent().position, &issue);
+                step.agent = factory();
+            }
+            (agent_res, _) => return (step, factsheet, agent_res),
+        }
+    }
+}
+
+// Keeps the urls that answer with 200
+async fn live_urls(urls: Vec<String>) -> Vec<String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_default();
+
+    let mut live = vec![];
+    for url in urls {
+        if matches!(check_status_code(&client, &url).await, Ok(200)) {
+            live.push(url);
+        }
+    }
+
+    live
+}
+
+// Changes the FactSheet the way a re-plan decided and returns the fields it changed
+fn apply_replan(factsheet: &mut FactSheet, action: ReplanAction) -> Result<Vec<FactField>, String> {
+    match action {
+        ReplanAction::SimplifyScope { project_scope } => {
+            factsheet.project_scope = Some(project_scope);
+            if !project_scope.is_external_urls_required {
+                factsheet.external_urls = None;
+            }
+            // The backend picks the template for the new scope
+            factsheet.code_template = None;
+
+            Ok(vec![
+                FactField::ProjectScope,
+                FactField::ExternalUrls,
+                FactField::CodeTemplate,
+            ])
+        }
+        ReplanAction::SwapExternalUrls { external_urls } => {
+            if external_urls.is_empty() {
+                return Err("None of the other external urls answered".to_string());
+            }
+            factsheet.external_urls = Some(external_urls);
+
+            Ok(vec![FactField::ExternalUrls])
+        }
+        ReplanAction::ChangeTemplate { template } => {
+            let template = TemplateRegistry::new(&factsheet.template_dirs)
+                .find(factsheet.web_framework, template)
+                .ok_or_else(|| {
+                    format!("No {} template for {}", template, factsheet.web_framework)
+                })?;
+            factsheet.code_template = Some(template);
+
+            Ok(vec![FactField::CodeTemplate])
+        }
+        ReplanAction::GiveUp => Ok(vec![]),
+    }
+}
+
+fn print_skipped_step(position: &str, step: &AgentStep) {
+    let skip = format!(
+        "Skipping {}, {} does not hold",
+        step.agent.get_attributes_from_agent().position,
+        step.when
+            .map(|condition| condition.to_string())
+            .unwrap_or_default()
+    );
+    PrintCommand::AICall.print_agent_msg(position, &skip);
+}
+
+fn approve_stage(name: &str) -> bool {
+    let answer = get_user_response(&format!("Run the {} stage? [y/n]", name));
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{
+        agent_basic::basic_trait::BasicTraits,
+        agents::agent_traits::FactField,
+        general::{framework::WebFramework, persistence::PersistenceBackend},
+    };
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn tests_managing_agent() {
         let usr_request = "Need a full stack app to track my habits";
 
-        let mut managing_agent = ManagingAgent::new(usr_request.to_string())
+        let mut managing_agent = ManagingAgent::new(usr_request.to_string(), RunOptions::default())
             .await
             .expect("Error creating managing agent");
 
@@ -90,4 +755,227 @@ mod tests {
 
         dbg!(managing_agent.factsheet);
     }
+
+    // Fails on its first run and writes the attempt it is on otherwise
+    #[derive(Debug)]
+    struct FlakyAgent {
+        attributes: BasicAgent,
+        attempt: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl SpecialFunctions for FlakyAgent {
+        fn get_attributes_from_agent(&self) -> &BasicAgent {
+            &self.attributes
+        }
+
+        fn reads(&self) -> &[FactField] {
+            &[]
+        }
+
+        fn writes(&self) -> &[FactField] {
+            &[FactField::DevopsArtifacts]
+        }
+
+        fn ai_functions(&self) -> &[&'static str] {
+            &[]
+        }
+
+        async fn execute(
+            &mut self,
+            factsheet: &mut FactSheet,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            factsheet.devops_artifacts = Some(vec![format!("attempt {}", self.attempt)]);
+            if self.attempt == 0 {
+                return Err("Dockerfile failed to lint".into());
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn tests_run_step_retries() {
+        let flaky = |attempt| -> Box<dyn SpecialFunctions> {
+            Box::new(FlakyAgent {
+                attributes: BasicAgent::new("Fails once", "Flaky agent"),
+                attempt,
+            })
+        };
+        let factsheet = FactSheet {
+            project_description: "build a website that lets users track their daily tasks"
+                .to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        };
+
+        let (_, _, agent_res) = run_step(AgentStep::new(flaky(0)), factsheet.clone()).await;
+        assert!(agent_res.is_err());
+
+        let step = AgentStep {
+            factory: Some(Arc::new(move || flaky(1))),
+            retries: 2,
+            ..AgentStep::new(flaky(0))
+        };
+        let (step, factsheet, agent_res) = run_step(step, factsheet).await;
+        assert_eq!(agent_res, Ok(()));
+        assert_eq!(
+            factsheet.devops_artifacts,
+            Some(vec!["attempt 1".to_string()])
+        );
+        assert_eq!(
+            step.agent.get_attributes_from_agent().position,
+            "Flaky agent"
+        );
+    }
+
+    // Finds only dead urls
+    #[derive(Debug)]
+    struct DeadUrlsAgent {
+        attributes: BasicAgent,
+    }
+
+    #[async_trait::async_trait]
+    impl SpecialFunctions for DeadUrlsAgent {
+        fn get_attributes_from_agent(&self) -> &BasicAgent {
+            &self.attributes
+        }
+
+        fn reads(&self) -> &[FactField] {
+            &[]
+        }
+
+        fn writes(&self) -> &[FactField] {
+            &[FactField::ExternalUrls]
+        }
+
+        fn ai_functions(&self) -> &[&'static str] {
+            &[]
+        }
+
+        async fn execute(
+            &mut self,
+            factsheet: &mut FactSheet,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            factsheet.external_urls = Some(vec![]);
+            Err(Box::new(ReplanTrigger::ExternalUrlsDead(vec![
+                "https://api.deadexchange.io/prices".to_string(),
+            ])))
+        }
+    }
+
+    #[tokio::test]
+    async fn tests_execute_steps_holds_for_replan() {
+        let mut managing_agent = ManagingAgent {
+            attributes: BasicAgent::new("Manages the test", "Project Manager"),
+            factsheet: FactSheet {
+                project_description: "build a website that shows crypto prices".to_string(),
+                project_scope: None,
+                external_urls: None,
+                backend_files: None,
+                api_endpoint_schema: None,
+                frontend_code: None,
+                devops_artifacts: None,
+                data_model: None,
+                web_framework: WebFramework::Actix,
+                persistence: PersistenceBackend::JsonFile,
+                template_dirs: vec![],
+                code_template: None,
+                edit_mode: EditMode::Patch,
+            },
+            stages: vec![],
+        };
+        let steps = || {
+            vec![
+                AgentStep::new(Box::new(DeadUrlsAgent {
+                    attributes: BasicAgent::new("Finds urls", "Url finder"),
+                })),
+                AgentStep::new(Box::new(AgentBackendDeveloper::new())),
+            ]
+        };
+
+        // The backend reads the urls, so it waits for the re-plan instead of building on none
+        let run = managing_agent.execute_steps(steps(), true).await;
+        assert_eq!(run.steps.len(), 2);
+        assert_eq!(run.pending, vec![1]);
+        assert_eq!(run.failures.len(), 1);
+        assert_eq!(run.failures[0].index, 0);
+        assert_eq!(run.failures[0].position, "Url finder");
+        assert!(run.failures[0]
+            .error
+            .trigger
+            .as_ref()
+            .is_some_and(|trigger| !trigger.reruns_agent()));
+        assert_eq!(managing_agent.factsheet.external_urls, Some(vec![]));
+    }
+
+    #[test]
+    fn tests_apply_replan() {
+        let mut factsheet = FactSheet {
+            project_description: "build a website that shows crypto prices".to_string(),
+            project_scope: Some(ProjectScope {
+                is_crud_required: true,
+                is_user_login_and_logout: false,
+                is_external_urls_required: true,
+            }),
+            external_urls: Some(vec![]),
+            backend_files: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Axum,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/templates")],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        };
+
+        let decision = serde_json::from_str::<ReplanDecision>(
+            r#"{"action": "change_template", "reason": "the proxy template has no storage", "template": "minimal"}"#,
+        )
+        .expect("Failed to decode re-plan");
+        assert_eq!(
+            apply_replan(&mut factsheet, decision.action),
+            Ok(vec![FactField::CodeTemplate])
+        );
+        assert_eq!(
+            factsheet
+                .code_template
+                .as_ref()
+                .map(|template| template.kind),
+            Some(TemplateKind::Minimal)
+        );
+
+        assert!(apply_replan(
+            &mut factsheet,
+            ReplanAction::SwapExternalUrls {
+                external_urls: vec![]
+            }
+        )
+        .is_err());
+
+        let project_scope = ProjectScope {
+            is_crud_required: true,
+            is_user_login_and_logout: false,
+            is_external_urls_required: false,
+        };
+        apply_replan(
+            &mut factsheet,
+            ReplanAction::SimplifyScope { project_scope },
+        )
+        .expect("Failed to simplify scope");
+        assert_eq!(factsheet.project_scope, Some(project_scope));
+        assert_eq!(factsheet.external_urls, None);
+        assert_eq!(factsheet.code_template, None);
+    }
 }
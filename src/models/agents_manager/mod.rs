@@ -1 +1,4 @@
+pub mod agent_graph;
+pub mod agent_registry;
 pub mod managing_agent;
+pub mod pipeline;
@@ -0,0 +1,783 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::models::{
+    agents::{
+        agent_plugin::Plugin,
+        agent_traits::{FactField, FactSheet, ProjectScope, SpecialFunctions},
+    },
+    general::llm::{LlmProvider, ModelChoice, ModelConfig, ModelSettings},
+};
+
+use super::agent_registry::{
+    load_registry, AgentFactory, AgentRegistry, AgentsConfig, PluginConfig,
+};
+
+pub const DEFAULT_PIPELINE_FILE: &str = "pipeline.toml";
+const DEFAULT_STAGE: &str = "build";
+const MAX_TEMPERATURE: f32 = 2.0;
+
+// pipeline.toml, or pipeline.yaml with the same layout. An agents.toml lineup loads as
+// its single build stage:
+//   [[plugins]]
+//   path = "plugins/libtodo_checker.so"
+//
+//   [defaults]
+//   provider = "openai"
+//   model = "gpt-4o"
+//
+//   [[stages]]
+//   name = "design"
+//   agents = ["architect", "data_model"]
+//
+//   [[stages]]
+//   name = "build"
+//   approval = true
+//   [[stages.agents]]
+//   name = "backend"
+//   temperature = 0.2
+//   max_tokens = 8000
+//   retries = 1
+//   on_failure = "stop"
+//   functions = { print_rest_api_endpoints = { model = "gpt-4o-mini", temperature = 0 } }
+//
+//   [[stages]]
+//   name = "audit"
+//   agents = [{ name = "security_auditor", when = "is_user_login_and_logout" }]
+// Stages run one after another and the agents of a stage in waves. Conditions are checked when
+// their stage starts and approval asks the user before it does
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineFile {
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+    // The model every agent falls back to
+    #[serde(default)]
+    pub defaults: ModelSettings,
+    pub stages: Vec<StageConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StageConfig {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_agents")]
+    pub agents: Vec<AgentConfig>,
+    #[serde(default)]
+    pub approval: bool,
+}
+
+#[derive(Debug, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct AgentConfig {
+    pub name: String,
+    pub provider: Option<LlmProvider>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    // Settings for single ai_functions of the agent, by function name
+    #[serde(default)]
+    pub functions: BTreeMap<String, ModelSettings>,
+    #[serde(default)]
+    pub retries: u8,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    pub when: Option<Condition>,
+}
+
+// What the pipeline does once an agent has failed every attempt
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    #[default]
+    Continue,
+    Stop,
+}
+
+// An agent is listed by name, or as a table with its name and settings
+struct AgentEntry(AgentConfig);
+
+impl<'de> Deserialize<'de> for AgentEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AgentVisitor;
+
+        impl<'de> Visitor<'de> for AgentVisitor {
+            type Value = AgentEntry;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an agent name or a table with its name and settings")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                Ok(AgentEntry(AgentConfig {
+                    name: name.to_string(),
+                    ..AgentConfig::default()
+                }))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                AgentConfig::deserialize(MapAccessDeserializer::new(map)).map(AgentEntry)
+            }
+        }
+
+        deserializer.deserialize_any(AgentVisitor)
+    }
+}
+
+fn deserialize_agents<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<AgentConfig>, D::Error> {
+    let entries = Vec::<AgentEntry>::deserialize(deserializer)?;
+    Ok(entries.into_iter().map(|AgentEntry(agent)| agent).collect())
+}
+
+// A ProjectScope flag the architect sets, or its negation with a leading !. A project
+// without a scope counts every flag as unset
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct Condition {
+    flag: ScopeFlag,
+    is_negated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScopeFlag {
+    CrudRequired,
+    UserLoginAndLogout,
+    ExternalUrlsRequired,
+}
+
+impl ScopeFlag {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::CrudRequired => "is_crud_required",
+            Self::UserLoginAndLogout => "is_user_login_and_logout",
+            Self::ExternalUrlsRequired => "is_external_urls_required",
+        }
+    }
+
+    fn is_set(&self, scope: &ProjectScope) -> bool {
+        match self {
+            Self::CrudRequired => scope.is_crud_required,
+            Self::UserLoginAndLogout => scope.is_user_login_and_logout,
+            Self::ExternalUrlsRequired => scope.is_external_urls_required,
+        }
+    }
+}
+
+impl Condition {
+    pub fn is_met(&self, factsheet: &FactSheet) -> bool {
+        let is_set = factsheet
+            .project_scope
+            .as_ref()
+            .is_some_and(|scope| self.flag.is_set(scope));
+        is_set != self.is_negated
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negated {
+            f.write_str("!")?;
+        }
+        f.write_str(self.flag.as_str())
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (name, is_negated) = match trimmed.strip_prefix('!') {
+            Some(name) => (name.trim(), true),
+            None => (trimmed, false),
+        };
+
+        let flag = [
+            ScopeFlag::CrudRequired,
+            ScopeFlag::UserLoginAndLogout,
+            ScopeFlag::ExternalUrlsRequired,
+        ]
+        .into_iter()
+        .find(|flag| flag.as_str() == name)
+        .ok_or_else(|| {
+            format!(
+                "Unknown condition {} (expected is_crud_required, is_user_login_and_logout \
+                or is_external_urls_required, optionally with a leading !)",
+                s
+            )
+        })?;
+
+        Ok(Self { flag, is_negated })
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+// An agent ready to run, with how the pipeline file says to run it
+pub struct AgentStep {
+    pub agent: Box<dyn SpecialFunctions>,
+    // Starts a fresh agent for a retry
+    pub factory: Option<AgentFactory>,
+    pub model: ModelConfig,
+    pub retries: u8,
+    pub on_failure: OnFailure,
+    pub when: Option<Condition>,
+}
+
+impl AgentStep {
+    pub fn new(agent: Box<dyn SpecialFunctions>) -> Self {
+        Self {
+            agent,
+            factory: None,
+            model: ModelConfig::default(),
+            retries: 0,
+            on_failure: OnFailure::default(),
+            when: None,
+        }
+    }
+
+    // The same step with a fresh agent, or None when the agent cannot be created again
+    pub fn restart(&self) -> Option<Self> {
+        let factory = self.factory.clone()?;
+
+        Some(Self {
+            agent: factory(),
+            factory: Some(factory),
+            model: self.model.clone(),
+            retries: self.retries,
+            on_failure: self.on_failure,
+            when: self.when,
+        })
+    }
+}
+
+pub struct Stage {
+    pub name: String,
+    pub approval: bool,
+    pub steps: Vec<AgentStep>,
+}
+
+impl Stage {
+    pub fn new(name: &str, agents: Vec<Box<dyn SpecialFunctions>>) -> Self {
+        Self {
+            name: name.to_string(),
+            approval: false,
+            steps: agents.into_iter().map(AgentStep::new).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PipelineReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl PipelineReport {
+    fn error(error: String) -> Self {
+        Self {
+            errors: vec![error],
+            warnings: vec![],
+        }
+    }
+}
+
+impl PipelineFile {
+    // YAML for .yaml and .yml files, TOML otherwise, agents.toml included. Plugin paths are
+    // relative to the file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+
+        let is_yaml = path
+            .extension()
+            .is_some_and(|extension| extension == "yaml" || extension == "yml");
+        let parsed = if is_yaml {
+            serde_yaml::from_str::<Self>(&content).map_err(|error| error.to_string())
+        } else {
+            toml::from_str::<Self>(&content).map_err(|error| error.to_string())
+        };
+        let mut pipeline = match parsed {
+            Ok(pipeline) => pipeline,
+            Err(_) if !is_yaml && toml::from_str::<AgentsConfig>(&content).is_ok() => {
+                return Self::from_agents_config(path);
+            }
+            Err(error) => return Err(format!("Invalid {}: {}", path.display(), error)),
+        };
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for plugin in &mut pipeline.plugins {
+            plugin.path = base.join(&plugin.path);
+        }
+
+        Ok(pipeline)
+    }
+
+    // Every registered agent in one stage, by position
+    fn default_for(registry: &AgentRegistry) -> Self {
+        Self::single_stage(AgentsConfig::default().lineup(registry), vec![])
+    }
+
+    // The agents.toml lineup as one stage, with its plugins
+    fn from_agents_config(path: &Path) -> Result<Self, String> {
+        let (registry, config) = load_registry(Some(path))?;
+        Ok(Self::single_stage(config.lineup(&registry), config.plugins))
+    }
+
+    fn single_stage(names: Vec<String>, plugins: Vec<PluginConfig>) -> Self {
+        let agents = names
+            .into_iter()
+            .map(|name| AgentConfig {
+                name,
+                ..AgentConfig::default()
+            })
+            .collect();
+
+        Self {
+            plugins,
+            defaults: ModelSettings::default(),
+            stages: vec![StageConfig {
+                name: DEFAULT_STAGE.to_string(),
+                agents,
+                approval: false,
+            }],
+        }
+    }
+
+    // The built-in agents plus the plugins the file loads
+    pub fn registry(&self) -> Result<AgentRegistry, String> {
+        let mut registry = AgentRegistry::with_builtin_agents();
+        for plugin in &self.plugins {
+            registry.register_plugin(Plugin::load(&plugin.path)?)?;
+        }
+
+        Ok(registry)
+    }
+
+    fn model_config(&self, config: &AgentConfig) -> ModelConfig {
+        ModelConfig {
+            agent: config.model_settings(),
+            functions: config.functions.clone(),
+            defaults: self.defaults.clone(),
+        }
+    }
+
+    pub fn runs_agent(&self, name: &str) -> bool {
+        self.stages
+            .iter()
+            .any(|stage| stage.agents.iter().any(|agent| agent.name == name))
+    }
+
+    // Errors stop the pipeline from running. Warnings point at agents reading a FactSheet
+    // field no agent before them writes, which usually means an agent is missing or misplaced
+    pub fn check(&self, registry: &AgentRegistry) -> PipelineReport {
+        let mut report = PipelineReport::default();
+        if self.stages.is_empty() {
+            report.errors.push("The pipeline has no stages".to_string());
+        }
+        check_model_settings(&self.defaults, "the defaults", &mut report);
+        let defaults = ModelConfig {
+            defaults: self.defaults.clone(),
+            ..ModelConfig::default()
+        };
+        check_model_choice(&defaults.resolve(""), "the defaults", &mut report);
+
+        let mut stage_names: Vec<&str> = vec![];
+        let mut agent_names: Vec<&str> = vec![];
+        let mut written = vec![FactField::ProjectDescription];
+
+        for stage in &self.stages {
+            if stage_names.contains(&stage.name.as_str()) {
+                report
+                    .errors
+                    .push(format!("Stage {} is declared twice", stage.name));
+            }
+            stage_names.push(&stage.name);
+
+            if stage.agents.is_empty() {
+                report
+                    .errors
+                    .push(format!("Stage {} has no agents", stage.name));
+            }
+
+            let written_before_stage = written.clone();
+            for config in &stage.agents {
+                let location = format!("{} in stage {}", config.name, stage.name);
+
+                if agent_names.contains(&config.name.as_str()) {
+                    report
+                        .errors
+                        .push(format!("Agent {} is listed more than once", config.name));
+                }
+                agent_names.push(&config.name);
+
+                let model = self.model_config(config);
+                check_model_settings(&config.model_settings(), &location, &mut report);
+                check_model_choice(&model.resolve(""), &location, &mut report);
+                for (function, settings) in &config.functions {
+                    let location = format!("{} of {}", function, location);
+                    check_model_settings(settings, &location, &mut report);
+                    check_model_choice(&model.resolve(function), &location, &mut report);
+                }
+
+                let Some(agent) = registry.create(&config.name) else {
+                    let names = registry
+                        .registrations()
+                        .iter()
+                        .map(|registration| registration.name.as_str())
+                        .collect::<Vec<_>>();
+                    report.errors.push(format!(
+                        "Unknown agent {}, registered agents are: {}",
+                        location,
+                        names.join(", ")
+                    ));
+                    continue;
+                };
+
+                for function in config.functions.keys() {
+                    if !agent.ai_functions().contains(&function.as_str()) {
+                        report.errors.push(format!(
+                            "Unknown ai_function {} for {}, it calls: {}",
+                            function,
+                            location,
+                            agent.ai_functions().join(", ")
+                        ));
+                    }
+                }
+
+                if let Some(condition) = config
+                    .when
+                    .filter(|_| !written_before_stage.contains(&FactField::ProjectScope))
+                {
+                    report.warnings.push(format!(
+                        "{} runs when {}, but no earlier stage works out the project scope",
+                        location, condition
+                    ));
+                }
+                for field in agent.reads() {
+                    if !written.contains(field) && !agent.writes().contains(field) {
+                        report.warnings.push(format!(
+                            "{} reads {:?}, which no agent before it writes",
+                            location, field
+                        ));
+                    }
+                }
+                written.extend(agent.writes());
+            }
+        }
+
+        report
+    }
+
+    fn build(&self, registry: &AgentRegistry) -> Vec<Stage> {
+        self.stages
+            .iter()
+            .map(|stage| Stage {
+                name: stage.name.clone(),
+                approval: stage.approval,
+                steps: stage
+                    .agents
+                    .iter()
+                    .filter_map(|config| {
+                        let factory = registry.factory(&config.name)?;
+                        Some(AgentStep {
+                            agent: factory(),
+                            factory: Some(factory),
+                            model: self.model_config(config),
+                            retries: config.retries,
+                            on_failure: config.on_failure,
+                            when: config.when,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl AgentConfig {
+    fn model_settings(&self) -> ModelSettings {
+        ModelSettings {
+            provider: self.provider,
+            model: self.model.clone(),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        }
+    }
+}
+
+// The temperature of each level is checked on its own, against the widest range any provider
+// takes. This checks the one a call ends up with against the provider it ends up calling
+fn check_model_choice(choice: &ModelChoice, location: &str, report: &mut PipelineReport) {
+    let limit = choice.provider.max_temperature();
+    if choice.temperature > limit && choice.temperature <= MAX_TEMPERATURE {
+        report.errors.push(format!(
+            "Temperature {} for {} is above the {} limit of {}",
+            choice.temperature, location, choice.provider, limit
+        ));
+    }
+}
+
+fn check_model_settings(settings: &ModelSettings, location: &str, report: &mut PipelineReport) {
+    if let Some(temperature) = settings
+        .temperature
+        .filter(|temperature| !(0.0..=MAX_TEMPERATURE).contains(temperature))
+    {
+        report.errors.push(format!(
+            "Temperature {} for {} is outside 0 to {}",
+            temperature, location, MAX_TEMPERATURE
+        ));
+    }
+    if settings
+        .model
+        .as_deref()
+        .is_some_and(|model| model.trim().is_empty())
+    {
+        report.errors.push(format!("Empty model for {}", location));
+    }
+    if settings.max_tokens == Some(0) {
+        report
+            .errors
+            .push(format!("max_tokens for {} is 0", location));
+    }
+}
+
+// Lints a pipeline file without running anything, for `pipeline check`
+pub fn check_pipeline(path: &Path) -> PipelineReport {
+    let pipeline = match PipelineFile::load(path) {
+        Ok(pipeline) => pipeline,
+        Err(error) => return PipelineReport::error(error),
+    };
+
+    match pipeline.registry() {
+        Ok(registry) => pipeline.check(&registry),
+        Err(error) => PipelineReport::error(error),
+    }
+}
+
+// What a run goes through, from a pipeline file or every registered agent in one stage
+pub struct Pipeline {
+    pub stages: Vec<Stage>,
+    pub defaults: ModelSettings,
+    pub warnings: Vec<String>,
+}
+
+pub fn load_pipeline(path: Option<&Path>) -> Result<Pipeline, String> {
+    let pipeline = path.map(PipelineFile::load).transpose()?;
+    let registry = match &pipeline {
+        Some(pipeline) => pipeline.registry()?,
+        None => AgentRegistry::with_builtin_agents(),
+    };
+    let pipeline = pipeline.unwrap_or_else(|| PipelineFile::default_for(&registry));
+
+    let report = pipeline.check(&registry);
+    if !report.errors.is_empty() {
+        return Err(report.errors.join("\n"));
+    }
+
+    Ok(Pipeline {
+        stages: pipeline.build(&registry),
+        defaults: pipeline.defaults,
+        warnings: report.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::models::general::{
+        edit_mode::EditMode, framework::WebFramework, persistence::PersistenceBackend,
+    };
+
+    const PIPELINE: &str = "[defaults]\n\
+        provider = \"ollama\"\n\n\
+        [[stages]]\n\
+        name = \"design\"\n\
+        agents = [\"architect\", \"data_model\"]\n\n\
+        [[stages]]\n\
+        name = \"build\"\n\
+        approval = true\n\
+        [[stages.agents]]\n\
+        name = \"backend\"\n\
+        provider = \"openai\"\n\
+        temperature = 0.2\n\
+        retries = 1\n\
+        on_failure = \"stop\"\n\
+        functions = { print_rest_api_endpoints = { model = \"gpt-4o-mini\", max_tokens = 2000 } }\n\n\
+        [[stages]]\n\
+        name = \"audit\"\n\
+        agents = [{ name = \"security_auditor\", when = \"is_user_login_and_logout\" }, \"devops\"]\n";
+
+    const YAML_PIPELINE: &str = "stages:\n  \
+        - name: build\n    \
+          agents:\n      \
+            - backend\n      \
+            - name: frontend\n        \
+              when: \"!is_crud_required\"\n        \
+              temperature: 3\n        \
+              functions:\n          \
+                print_frontend_webpage_code: { max_tokens: 0 }\n          \
+                print_frontend_page: { model: gpt-4o-mini }\n  \
+        - name: build\n    \
+          agents: [architect, tester]\n";
+
+    fn write_pipeline(file_name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), file_name));
+        fs::write(&path, content).expect("Failed to write pipeline");
+        path
+    }
+
+    #[test]
+    fn tests_check_pipeline() {
+        let path = write_pipeline("pipeline.toml", PIPELINE);
+        let pipeline = PipelineFile::load(&path).expect("Failed to load pipeline");
+
+        assert_eq!(pipeline.stages.len(), 3);
+        assert_eq!(
+            pipeline.stages[1].agents[0],
+            AgentConfig {
+                name: "backend".to_string(),
+                provider: Some(LlmProvider::OpenAi),
+                temperature: Some(0.2),
+                functions: BTreeMap::from([(
+                    "print_rest_api_endpoints".to_string(),
+                    ModelSettings {
+                        model: Some("gpt-4o-mini".to_string()),
+                        max_tokens: Some(2000),
+                        ..ModelSettings::default()
+                    }
+                )]),
+                retries: 1,
+                on_failure: OnFailure::Stop,
+                ..AgentConfig::default()
+            }
+        );
+        assert_eq!(check_pipeline(&path), PipelineReport::default());
+
+        let pipeline = load_pipeline(Some(&path)).expect("Failed to load pipeline");
+        assert!(pipeline.warnings.is_empty());
+        assert_eq!(
+            pipeline
+                .stages
+                .iter()
+                .map(|stage| (stage.name.as_str(), stage.approval, stage.steps.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("design", false, 2),
+                ("build", true, 1),
+                ("audit", false, 2)
+            ]
+        );
+        fs::remove_file(&path).expect("Failed to clean up pipeline");
+
+        let path = write_pipeline("pipeline.yaml", YAML_PIPELINE);
+        let report = check_pipeline(&path);
+        assert_eq!(
+            report.errors,
+            vec![
+                "Temperature 3 for frontend in stage build is outside 0 to 2",
+                "max_tokens for print_frontend_webpage_code of frontend in stage build is 0",
+                "Unknown ai_function print_frontend_page for frontend in stage build, it calls: \
+                print_frontend_webpage_code, print_fixed_frontend_code",
+                "Stage build is declared twice",
+                "Unknown agent tester in stage build, registered agents are: architect, \
+                data_model, backend, security_auditor, devops, frontend",
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![
+                "backend in stage build reads ProjectScope, which no agent before it writes",
+                "backend in stage build reads ExternalUrls, which no agent before it writes",
+                "backend in stage build reads DataModel, which no agent before it writes",
+                "frontend in stage build runs when !is_crud_required, but no earlier stage \
+                works out the project scope",
+            ]
+        );
+        assert!(load_pipeline(Some(&path)).is_err());
+        fs::remove_file(&path).expect("Failed to clean up pipeline");
+
+        // Anthropic only takes temperatures up to 1, also when the provider comes from the defaults
+        let path = write_pipeline(
+            "anthropic.toml",
+            "[defaults]\nprovider = \"anthropic\"\n\n[[stages]]\nname = \"build\"\n\
+            agents = [{ name = \"architect\", temperature = 1.5 }, \
+            { name = \"backend\", provider = \"openai\", temperature = 1.5 }]\n",
+        );
+        assert_eq!(
+            check_pipeline(&path).errors,
+            vec!["Temperature 1.5 for architect in stage build is above the anthropic limit of 1"]
+        );
+        fs::remove_file(&path).expect("Failed to clean up pipeline");
+
+        // agents.toml lineups run as one build stage
+        let path = write_pipeline("agents.toml", "agents = [\"backend\", \"architect\"]\n");
+        let pipeline = PipelineFile::load(&path).expect("Failed to load agents.toml");
+        assert_eq!(pipeline.stages.len(), 1);
+        assert_eq!(
+            pipeline.stages[0]
+                .agents
+                .iter()
+                .map(|agent| agent.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["backend", "architect"]
+        );
+        assert!(check_pipeline(&path).errors.is_empty());
+        fs::remove_file(&path).expect("Failed to clean up pipeline");
+
+        let path = write_pipeline("typo.toml", "[[stage]]\nname = \"build\"\n");
+        assert_eq!(check_pipeline(&path).errors.len(), 1);
+        fs::remove_file(&path).expect("Failed to clean up pipeline");
+
+        let pipeline = load_pipeline(None).expect("Failed to load default pipeline");
+        assert_eq!(pipeline.stages.len(), 1);
+        assert_eq!(pipeline.stages[0].steps.len(), 6);
+    }
+
+    #[test]
+    fn tests_condition() {
+        let condition: Condition = "!is_user_login_and_logout"
+            .parse()
+            .expect("Failed to parse");
+        let mut factsheet = FactSheet {
+            project_description: "build a website that lets users track their daily tasks"
+                .to_string(),
+            project_scope: None,
+            external_urls: None,
+            backend_files: None,
+            api_endpoint_schema: None,
+            frontend_code: None,
+            devops_artifacts: None,
+            data_model: None,
+            web_framework: WebFramework::Actix,
+            persistence: PersistenceBackend::JsonFile,
+            template_dirs: vec![],
+            code_template: None,
+            edit_mode: EditMode::Patch,
+        };
+
+        assert!(condition.is_met(&factsheet));
+        factsheet.project_scope = Some(ProjectScope {
+            is_crud_required: true,
+            is_user_login_and_logout: true,
+            is_external_urls_required: false,
+        });
+        assert!(!condition.is_met(&factsheet));
+        assert_eq!(condition.to_string(), "!is_user_login_and_logout");
+        assert!("is_admin".parse::<Condition>().is_err());
+    }
+}
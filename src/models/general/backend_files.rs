@@ -0,0 +1,255 @@
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    path::{Component, Path},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const MAIN_FILE: &str = "src/main.rs";
+pub const MANIFEST_FILE: &str = "Cargo.toml";
+pub const TESTS_FILE: &str = "src/api_tests.rs";
+
+const FILE_START: &str = "=== FILE:";
+const FILE_DELIMITER: &str = "===";
+const FILE_END: &str = "=== END FILE ===";
+const TESTS_MODULE_DECLARATION: &str = "#[cfg(test)]\nmod api_tests;";
+
+// The generated backend as relative path -> contents, e.g. Cargo.toml, src/main.rs,
+// src/routes/tasks.rs. The LLM reads and writes it in the delimited format:
+//   === FILE: src/main.rs ===
+//   ...
+//   === END FILE ===
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct BackendFiles(BTreeMap<String, String>);
+
+impl BackendFiles {
+    // Entries that cannot be written, outside Cargo.toml and src/ or repeated, are skipped and
+    // described next to the files that were kept. Only output without any FILE header fails
+    pub fn parse_delimited(output: &str) -> Result<(Self, Vec<String>), String> {
+        let mut files = BTreeMap::new();
+        let mut skipped = vec![];
+        let mut has_header = false;
+        let mut current: Option<(String, Vec<&str>)> = None;
+
+        for line in output.lines() {
+            let trimmed = line.trim();
+
+            if trimmed == FILE_END {
+                match current.take() {
+                    Some((path, lines)) => insert_file(&mut files, &mut skipped, path, &lines),
+                    None => skipped.push("END FILE without a FILE header".to_string()),
+                }
+                continue;
+            }
+
+            if let Some(path) = trimmed
+                .strip_prefix(FILE_START)
+                .and_then(|rest| rest.strip_suffix(FILE_DELIMITER))
+            {
+                // A missing END FILE is forgiven, the next header closes the file
+                if let Some((previous, lines)) = current.take() {
+                    insert_file(&mut files, &mut skipped, previous, &lines);
+                }
+                has_header = true;
+                current = Some((path.trim().to_string(), vec![]));
+                continue;
+            }
+
+            if let Some((_, lines)) = current.as_mut() {
+                lines.push(line);
+            }
+        }
+
+        if let Some((path, lines)) = current {
+            insert_file(&mut files, &mut skipped, path, &lines);
+        }
+
+        if !has_header {
+            return Err("Generated project has no FILE headers".to_string());
+        }
+        if !files.contains_key(MAIN_FILE) {
+            skipped.push(format!("Generated project has no {}", MAIN_FILE));
+        }
+
+        Ok((Self(files), skipped))
+    }
+
+    pub fn to_delimited(&self) -> String {
+        self.0
+            .iter()
+            .map(|(path, content)| {
+                format!(
+                    "{} {} {}\n{}\n{}\n",
+                    FILE_START,
+                    path,
+                    FILE_DELIMITER,
+                    content.trim_end(),
+                    FILE_END
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.0.get(path).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, path: &str, content: &str) {
+        self.0.insert(path.to_string(), content.to_string());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(path, content)| (path.as_str(), content.as_str()))
+    }
+
+    pub fn rust_sources(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.iter().filter(|(path, _)| path.ends_with(".rs"))
+    }
+
+    // Adds the integration tests and makes sure main.rs declares them
+    pub fn insert_tests(&mut self, content: &str) {
+        self.insert(TESTS_FILE, content);
+
+        if let Some(main_code) = self.0.get_mut(MAIN_FILE) {
+            if !main_code.contains("mod api_tests;") {
+                *main_code = format!("{}\n\n{}\n", main_code.trim_end(), TESTS_MODULE_DECLARATION);
+            }
+        }
+    }
+}
+
+// Only Cargo.toml and Rust sources under src/ may be written, never outside the project
+pub fn is_allowed_path(path: &str) -> bool {
+    let relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+
+    relative && (path == MANIFEST_FILE || (path.starts_with("src/") && path.ends_with(".rs")))
+}
+
+// Keeps the first copy of a file that appears twice
+fn insert_file(
+    files: &mut BTreeMap<String, String>,
+    skipped: &mut Vec<String>,
+    path: String,
+    lines: &[&str],
+) {
+    if !is_allowed_path(&path) {
+        skipped.push(format!(
+            "Skipped generated file {}, it is outside Cargo.toml and src/",
+            path
+        ));
+        return;
+    }
+
+    match files.entry(path) {
+        Entry::Occupied(entry) => skipped.push(format!(
+            "Skipped generated file {}, it appears twice",
+            entry.key()
+        )),
+        Entry::Vacant(entry) => {
+            entry.insert(strip_code_fence(lines).join("\n") + "\n");
+        }
+    }
+}
+
+// Drops a ```rust ... ``` fence wrapped around a single file, and the blank lines around it
+fn strip_code_fence<'a, 'b>(lines: &'b [&'a str]) -> &'b [&'a str] {
+    let mut lines = lines;
+
+    while let [first, rest @ ..] = lines {
+        if !first.trim().is_empty() {
+            break;
+        }
+        lines = rest;
+    }
+    while let [rest @ .., last] = lines {
+        if !last.trim().is_empty() {
+            break;
+        }
+        lines = rest;
+    }
+
+    match lines {
+        [first, inner @ .., last] if first.trim().starts_with("```") && last.trim() == "```" => {
+            inner
+        }
+        _ => lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_parse_delimited() {
+        let output = "Here is the project:\n\
+            === FILE: src/main.rs ===\n\
+            ```rust\n\
+            mod routes;\n\
+            fn main() {}\n\
+            ```\n\
+            === END FILE ===\n\
+            === FILE: src/routes/mod.rs ===\n\
+            pub mod tasks;\n\
+            === FILE: src/routes/tasks.rs ===\n\
+            pub async fn create() {}\n\
+            === END FILE ===\n";
+
+        let (files, skipped) =
+            BackendFiles::parse_delimited(output).expect("Failed to parse files");
+        assert!(skipped.is_empty());
+
+        assert_eq!(files.get(MAIN_FILE), Some("mod routes;\nfn main() {}\n"));
+        assert_eq!(files.get("src/routes/mod.rs"), Some("pub mod tasks;\n"));
+        assert_eq!(files.iter().count(), 3);
+        assert_eq!(
+            BackendFiles::parse_delimited(&files.to_delimited()),
+            Ok((files, vec![]))
+        );
+    }
+
+    #[test]
+    fn tests_parse_delimited_skips_bad_entries() {
+        let file =
+            |path: &str| format!("=== FILE: {} ===\nfn main() {{}}\n=== END FILE ===\n", path);
+
+        assert!(BackendFiles::parse_delimited("fn main() {}").is_err());
+
+        let (files, skipped) =
+            BackendFiles::parse_delimited(&file("src/lib.rs")).expect("Failed to parse files");
+        assert_eq!(files.get("src/lib.rs"), Some("fn main() {}\n"));
+        assert_eq!(skipped, vec!["Generated project has no src/main.rs"]);
+
+        for bad_path in [MAIN_FILE, "../build.rs", "/etc/passwd", "static/index.html"] {
+            let output = file(MAIN_FILE) + &file("src/db.rs") + &file(bad_path);
+            let (files, skipped) =
+                BackendFiles::parse_delimited(&output).expect("Failed to parse files");
+            assert_eq!(
+                files.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+                vec!["src/db.rs", MAIN_FILE]
+            );
+            assert_eq!(skipped.len(), 1, "{} was not skipped", bad_path);
+        }
+    }
+
+    #[test]
+    fn tests_insert_tests() {
+        let mut files = BackendFiles::default();
+        files.insert(MAIN_FILE, "fn main() {}\n");
+
+        files.insert_tests("use super::*;\n");
+        files.insert_tests("use super::*;\n");
+
+        assert_eq!(
+            files.get(MAIN_FILE),
+            Some("fn main() {}\n\n#[cfg(test)]\nmod api_tests;\n")
+        );
+        assert_eq!(files.get(TESTS_FILE), Some("use super::*;\n"));
+    }
+}
@@ -0,0 +1,43 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+// How the backend developer changes code that already exists. Patch asks the LLM for
+// search/replace blocks and only rewrites every file when they fail to apply
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EditMode {
+    #[default]
+    Patch,
+    Rewrite,
+}
+
+impl EditMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Rewrite => "rewrite",
+        }
+    }
+}
+
+impl fmt::Display for EditMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EditMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "patch" | "diff" => Ok(Self::Patch),
+            "rewrite" | "full" => Ok(Self::Rewrite),
+            other => Err(format!(
+                "Unknown edit mode: {} (expected patch or rewrite)",
+                other
+            )),
+        }
+    }
+}
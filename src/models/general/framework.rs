@@ -0,0 +1,72 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+// Web framework the generated backend is written with. Each one ships a Cargo.toml and a set of
+// code templates under assets/templates/<framework>/
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WebFramework {
+    #[default]
+    Actix,
+    Axum,
+    Rocket,
+}
+
+impl WebFramework {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Actix => "actix",
+            Self::Axum => "axum",
+            Self::Rocket => "rocket",
+        }
+    }
+
+    // Libraries in the template Cargo.toml, the only ones generated code may use
+    pub fn installed_libraries(&self) -> &'static str {
+        match self {
+            Self::Actix => "reqwest, serde, serde_json, tokio, actix-web, async-trait, actix-cors, argon2 for hashing passwords",
+            Self::Axum => {
+                "reqwest, serde, serde_json, tokio, axum 0.8 (path params are written /{id}), async-trait, tower-http with the cors feature, argon2 for hashing passwords. Tests may also use tower with the util feature and http-body-util"
+            }
+            Self::Rocket => "reqwest, serde, serde_json, tokio, rocket 0.5 with the json feature, async-trait, argon2 for hashing passwords",
+        }
+    }
+
+    // How integration tests drive the app in process, without binding a port
+    pub fn testing_style(&self) -> &'static str {
+        match self {
+            Self::Actix => {
+                "Tests are marked #[actix_web::test] and build their own App with actix_web::test::init_service, registering the same routes as main"
+            }
+            Self::Axum => {
+                "Tests are marked #[tokio::test], build the Router with app(...) and send requests with tower::ServiceExt::oneshot, reading bodies with http_body_util::BodyExt::collect"
+            }
+            Self::Rocket => {
+                "Tests are marked #[rocket::async_test] and drive rocket() through rocket::local::asynchronous::Client::tracked"
+            }
+        }
+    }
+}
+
+impl fmt::Display for WebFramework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebFramework {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "actix" | "actix-web" | "actix_web" => Ok(Self::Actix),
+            "axum" => Ok(Self::Axum),
+            "rocket" => Ok(Self::Rocket),
+            other => Err(format!(
+                "Unknown web framework: {} (expected actix, axum or rocket)",
+                other
+            )),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Clone)]
@@ -11,6 +13,178 @@ pub struct ChatCompletion {
     pub model: String,
     pub messages: Vec<Message>,
     pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+// Anthropic's messages API takes max_tokens and answers with content blocks
+#[derive(Debug, Serialize)]
+pub struct AnthropicRequest {
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicContent {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicResponse {
+    pub content: Vec<AnthropicContent>,
+}
+
+// The body of a failed messages call, e.g. an overloaded or invalid_request error
+#[derive(Debug, Deserialize)]
+pub struct AnthropicErrorDetail {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnthropicError {
+    pub error: AnthropicErrorDetail,
+}
+
+// Where LLM calls go. Ollama serves the OpenAI chat completions API locally
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    #[default]
+    OpenAi,
+    Anthropic,
+    Ollama,
+}
+
+impl LlmProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Anthropic => "anthropic",
+            Self::Ollama => "ollama",
+        }
+    }
+
+    // Anthropic rejects temperatures above 1, OpenAI and Ollama go up to 2
+    pub fn max_temperature(&self) -> f32 {
+        match self {
+            Self::Anthropic => 1.0,
+            Self::OpenAi | Self::Ollama => 2.0,
+        }
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Self::OpenAi => "gpt-4o",
+            Self::Anthropic => "claude-sonnet-4-5",
+            Self::Ollama => "llama3.1",
+        }
+    }
+}
+
+impl fmt::Display for LlmProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LlmProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "anthropic" => Ok(Self::Anthropic),
+            "ollama" => Ok(Self::Ollama),
+            other => Err(format!(
+                "Unknown provider: {} (expected openai, anthropic or ollama)",
+                other
+            )),
+        }
+    }
+}
+
+pub const DEFAULT_TEMPERATURE: f32 = 0.1;
+// Anthropic needs a limit, the other providers only get one when it is set
+pub const DEFAULT_ANTHROPIC_MAX_TOKENS: u32 = 8192;
+
+// Overrides for the model an agent or ai_function calls, anything left out falls through
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ModelSettings {
+    pub provider: Option<LlmProvider>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl ModelSettings {
+    // Fills whatever is unset from the fallback
+    pub fn or(&self, fallback: &ModelSettings) -> ModelSettings {
+        ModelSettings {
+            provider: self.provider.or(fallback.provider),
+            model: self.model.clone().or_else(|| fallback.model.clone()),
+            temperature: self.temperature.or(fallback.temperature),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+        }
+    }
+}
+
+// The settings an agent runs with. An ai_function's own settings come first, then the agent's,
+// then the pipeline defaults, then the provider's default model at temperature 0.1
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelConfig {
+    pub agent: ModelSettings,
+    pub functions: BTreeMap<String, ModelSettings>,
+    pub defaults: ModelSettings,
+}
+
+impl ModelConfig {
+    pub fn resolve(&self, ai_function: &str) -> ModelChoice {
+        let levels = self
+            .functions
+            .get(ai_function)
+            .into_iter()
+            .chain([&self.agent, &self.defaults])
+            .collect::<Vec<_>>();
+        let provider_at = |index: usize| {
+            levels[index..]
+                .iter()
+                .find_map(|level| level.provider)
+                .unwrap_or_default()
+        };
+        let settings = levels
+            .iter()
+            .fold(ModelSettings::default(), |settings, level| {
+                settings.or(level)
+            });
+
+        // A model only carries over from a level that calls the same provider
+        let provider = provider_at(0);
+        let model = (0..levels.len())
+            .filter(|&index| provider_at(index) == provider)
+            .find_map(|index| levels[index].model.clone())
+            .unwrap_or_else(|| provider.default_model().to_string());
+
+        ModelChoice {
+            provider,
+            model,
+            temperature: settings.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            max_tokens: settings.max_tokens,
+        }
+    }
+}
+
+// What one LLM call ends up using, as recorded in the run log
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ModelChoice {
+    pub provider: LlmProvider,
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,3 +201,75 @@ pub struct ApiChoice {
 pub struct ApiResponse {
     pub choices: Vec<ApiChoice>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_resolve_model_choice() {
+        let config = ModelConfig {
+            agent: ModelSettings {
+                model: Some("gpt-4o-mini".to_string()),
+                temperature: Some(0.3),
+                ..ModelSettings::default()
+            },
+            functions: BTreeMap::from([
+                (
+                    "print_project_scope".to_string(),
+                    ModelSettings {
+                        temperature: Some(0.0),
+                        max_tokens: Some(500),
+                        ..ModelSettings::default()
+                    },
+                ),
+                (
+                    "print_backend_webserver_code".to_string(),
+                    ModelSettings {
+                        provider: Some(LlmProvider::Anthropic),
+                        ..ModelSettings::default()
+                    },
+                ),
+            ]),
+            defaults: ModelSettings {
+                max_tokens: Some(4000),
+                ..ModelSettings::default()
+            },
+        };
+
+        assert_eq!(
+            config.resolve("print_project_scope"),
+            ModelChoice {
+                provider: LlmProvider::OpenAi,
+                model: "gpt-4o-mini".to_string(),
+                temperature: 0.0,
+                max_tokens: Some(500),
+            }
+        );
+        assert_eq!(
+            config.resolve("print_external_urls"),
+            ModelChoice {
+                provider: LlmProvider::OpenAi,
+                model: "gpt-4o-mini".to_string(),
+                temperature: 0.3,
+                max_tokens: Some(4000),
+            }
+        );
+        // The agent's OpenAI model does not follow the function over to Anthropic
+        assert_eq!(
+            config.resolve("print_backend_webserver_code").model,
+            LlmProvider::Anthropic.default_model()
+        );
+        assert_eq!(
+            ModelConfig::default().resolve("print_project_scope"),
+            ModelChoice {
+                provider: LlmProvider::OpenAi,
+                model: "gpt-4o".to_string(),
+                temperature: DEFAULT_TEMPERATURE,
+                max_tokens: None,
+            }
+        );
+        assert_eq!("Ollama".parse(), Ok(LlmProvider::Ollama));
+        assert!("bedrock".parse::<LlmProvider>().is_err());
+    }
+}
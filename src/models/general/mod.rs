@@ -1 +1,7 @@
+pub mod backend_files;
+pub mod edit_mode;
+pub mod framework;
 pub mod llm;
+pub mod persistence;
+pub mod schema;
+pub mod template;
@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{
+    de::Deserializer,
+    ser::{SerializeMap, SerializeSeq, Serializer},
+    Deserialize, Serialize,
+};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+    Options,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Post => "post",
+            Self::Put => "put",
+            Self::Patch => "patch",
+            Self::Delete => "delete",
+            Self::Head => "head",
+            Self::Options => "options",
+        }
+    }
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "get" => Ok(Self::Get),
+            "post" => Ok(Self::Post),
+            "put" => Ok(Self::Put),
+            "patch" => Ok(Self::Patch),
+            "delete" => Ok(Self::Delete),
+            "head" => Ok(Self::Head),
+            "options" => Ok(Self::Options),
+            other => Err(format!("Unknown http method: {}", other)),
+        }
+    }
+}
+
+// Shape of a request body or response. Serialized the same way the endpoint extractor
+// prints it: "string", "number", "bool", {"field": ...} or [item]
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeSchema {
+    String,
+    Number,
+    Bool,
+    Object(BTreeMap<String, TypeSchema>),
+    Array(Box<TypeSchema>),
+    Unknown,
+}
+
+impl TypeSchema {
+    // Reads a body from the LLM format, where "None", "null" and "" mean there is no body
+    pub fn from_llm_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => None,
+            Value::String(name) => match name.trim().to_lowercase().as_str() {
+                "" | "none" | "null" => None,
+                _ => Some(Self::from_type_name(name)),
+            },
+            other => Some(Self::from_value(other)),
+        }
+    }
+
+    // Placeholder value of this shape, used for example request bodies
+    pub fn example_value(&self) -> Value {
+        match self {
+            Self::String => Value::String("string".to_string()),
+            Self::Number => Value::from(0),
+            Self::Bool => Value::Bool(false),
+            Self::Unknown => Value::Null,
+            Self::Array(item) => Value::Array(vec![item.example_value()]),
+            Self::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), field.example_value()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Unknown,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(_) => Self::Number,
+            Value::String(name) => Self::from_type_name(name),
+            Value::Array(items) => Self::Array(Box::new(
+                items.first().map_or(Self::Unknown, Self::from_value),
+            )),
+            Value::Object(fields) => Self::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| (key.clone(), Self::from_value(field)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn from_type_name(name: &str) -> Self {
+        let name = name.trim().to_lowercase();
+        let name = name.strip_prefix("option<").unwrap_or(&name);
+
+        match name.trim_end_matches('>') {
+            "string" | "str" | "&str" | "char" | "date" | "datetime" | "uuid" => Self::String,
+            "number" | "integer" | "int" | "float" | "u8" | "u16" | "u32" | "u64" | "usize"
+            | "i8" | "i16" | "i32" | "i64" | "isize" | "f32" | "f64" => Self::Number,
+            "bool" | "boolean" => Self::Bool,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl Serialize for TypeSchema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::String => serializer.serialize_str("string"),
+            Self::Number => serializer.serialize_str("number"),
+            Self::Bool => serializer.serialize_str("bool"),
+            Self::Unknown => serializer.serialize_str("unknown"),
+            Self::Array(item) => {
+                let mut seq = serializer.serialize_seq(Some(1))?;
+                seq.serialize_element(item)?;
+                seq.end()
+            }
+            Self::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, field) in fields {
+                    map.serialize_entry(key, field)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TypeSchema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_value(&Value::deserialize(deserializer)?))
+    }
+}
+
+// Names of the {param} segments of a route such as /user/{user_id}/task/{id}
+pub fn parse_path_params(route: &str) -> Vec<String> {
+    route
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(|param| param.split(':').next().unwrap_or(param).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn tests_type_schema_from_llm_value() {
+        assert_eq!(TypeSchema::from_llm_value(&json!("None")), None);
+        assert_eq!(TypeSchema::from_llm_value(&Value::Null), None);
+        assert_eq!(
+            TypeSchema::from_llm_value(&json!("not_provided")),
+            Some(TypeSchema::Unknown)
+        );
+
+        let schema = TypeSchema::from_llm_value(&json!([{ "id": "number", "done": "bool" }]));
+        let expected = TypeSchema::Array(Box::new(TypeSchema::Object(BTreeMap::from([
+            ("done".to_string(), TypeSchema::Bool),
+            ("id".to_string(), TypeSchema::Number),
+        ]))));
+        assert_eq!(schema, Some(expected.clone()));
+
+        let round_trip: TypeSchema =
+            serde_json::from_value(serde_json::to_value(&expected).unwrap()).unwrap();
+        assert_eq!(round_trip, expected);
+
+        assert_eq!(
+            expected.example_value(),
+            json!([{ "done": false, "id": 0 }])
+        );
+    }
+
+    #[test]
+    fn tests_parse_path_params() {
+        assert_eq!(
+            parse_path_params("/user/{user_id}/task/{id}"),
+            vec!["user_id", "id"]
+        );
+        assert!(parse_path_params("/task").is_empty());
+    }
+
+    #[test]
+    fn tests_http_method() {
+        assert_eq!("DELETE".parse::<HttpMethod>(), Ok(HttpMethod::Delete));
+        assert_eq!(" head ".parse::<HttpMethod>(), Ok(HttpMethod::Head));
+        assert!("fetch".parse::<HttpMethod>().is_err());
+
+        let methods: Vec<HttpMethod> =
+            serde_json::from_value(json!(["head", "options"])).expect("Failed to decode");
+        assert_eq!(methods, vec![HttpMethod::Head, HttpMethod::Options]);
+        assert_eq!(HttpMethod::Options.to_string(), "options");
+    }
+}
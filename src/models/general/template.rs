@@ -0,0 +1,94 @@
+use std::{fmt, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::agents::agent_traits::ProjectScope;
+
+use super::framework::WebFramework;
+
+// Code templates the backend developer starts from. Each framework ships one of every kind
+// under assets/templates/<framework>/<kind>/main.rs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateKind {
+    Crud,
+    CrudAuth,
+    ExternalApiProxy,
+    Minimal,
+}
+
+impl TemplateKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Crud => "crud",
+            Self::CrudAuth => "crud_auth",
+            Self::ExternalApiProxy => "external_api_proxy",
+            Self::Minimal => "minimal",
+        }
+    }
+
+    // Login needs stored users, so it wins over plain CRUD. A project that stores data and
+    // calls external urls still starts from the CRUD template
+    pub fn from_scope(scope: Option<&ProjectScope>) -> Self {
+        let Some(scope) = scope else {
+            return Self::Minimal;
+        };
+
+        if scope.is_user_login_and_logout {
+            Self::CrudAuth
+        } else if scope.is_crud_required {
+            Self::Crud
+        } else if scope.is_external_urls_required {
+            Self::ExternalApiProxy
+        } else {
+            Self::Minimal
+        }
+    }
+}
+
+impl fmt::Display for TemplateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// The template a backend was generated from, recorded on the FactSheet
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CodeTemplate {
+    pub kind: TemplateKind,
+    pub framework: WebFramework,
+    pub code_path: PathBuf,
+    pub manifest_path: PathBuf,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_template_kind_from_scope() {
+        let scope = |crud, login, external| ProjectScope {
+            is_crud_required: crud,
+            is_user_login_and_logout: login,
+            is_external_urls_required: external,
+        };
+
+        assert_eq!(TemplateKind::from_scope(None), TemplateKind::Minimal);
+        assert_eq!(
+            TemplateKind::from_scope(Some(&scope(false, false, false))),
+            TemplateKind::Minimal
+        );
+        assert_eq!(
+            TemplateKind::from_scope(Some(&scope(true, false, true))),
+            TemplateKind::Crud
+        );
+        assert_eq!(
+            TemplateKind::from_scope(Some(&scope(true, true, false))),
+            TemplateKind::CrudAuth
+        );
+        assert_eq!(
+            TemplateKind::from_scope(Some(&scope(false, false, true))),
+            TemplateKind::ExternalApiProxy
+        );
+    }
+}
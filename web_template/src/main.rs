@@ -9,10 +9,15 @@ use reqwest::Client as HttpClient;
 use async_trait::async_trait;
 
 use std::collections::HashMap;
+use std::env;
 use std::fs;
 use std::io::Write;
 use std::sync::Mutex;
 
+fn database_path() -> String {
+    env::var("DATABASE_PATH").unwrap_or_else(|_| "database.json".to_string())
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Task {
     id: u64,
@@ -74,13 +79,13 @@ impl Database {
     // DATABASE SAVING
     fn save_to_file(&self) -> std::io::Result<()> {
         let data: String = serde_json::to_string(&self)?;
-        let mut file: fs::File = fs::File::create("database.json")?;
+        let mut file: fs::File = fs::File::create(database_path())?;
         file.write_all(data.as_bytes())?;
         Ok(())
     }
 
     fn load_from_file() -> std::io::Result<Self> {
-        let file_content: String = fs::read_to_string("database.json")?;
+        let file_content: String = fs::read_to_string(database_path())?;
         let db: Database = serde_json::from_str(&file_content)?;
         Ok(db)
     }
@@ -142,6 +147,15 @@ async fn login(app_state: web::Data<AppState>, user: web::Json<User>) -> impl Re
     }
 }
 
+async fn index() -> impl Responder {
+    match fs::read_to_string("static/index.html") {
+        Ok(html) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     let db: Database = match Database::load_from_file() {
@@ -165,6 +179,7 @@ async fn main() -> std::io::Result<()> {
                     .max_age(3600),
             )
             .app_data(data.clone())
+            .route("/", web::get().to(index))
             .route("/task", web::post().to(create_task))
             .route("/task", web::get().to(read_all_tasks))
             .route("/task", web::put().to(update_task))
@@ -173,7 +188,7 @@ async fn main() -> std::io::Result<()> {
             .route("/register", web::post().to(register))
             .route("/login", web::post().to(login))
     })
-    .bind("127.0.0.1:8080")?
+    .bind(env::var("SERVER_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8080".to_string()))?
     .run()
     .await
 }